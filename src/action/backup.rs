@@ -0,0 +1,28 @@
+use crate::cli::exit::or_exit;
+use dialoguer::Confirm;
+use dialoguer::theme::ColorfulTheme;
+use docu::store::sqlite::SqliteStore;
+use std::path::Path;
+
+pub fn backup_to_file(store: &SqliteStore, path: &Path) {
+    or_exit(store.backup(path));
+    println!("Backed up to {}", path.display());
+}
+
+pub fn restore_from_file(store: &SqliteStore, path: &Path, yes: bool) {
+    if !yes
+        && !Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "Replace all of your scriptlets with the backup in {}?",
+                path.display()
+            ))
+            .default(false)
+            .interact()
+            .expect("Failed to read confirmation")
+    {
+        println!("Restore cancelled");
+        return;
+    }
+    or_exit(store.restore_backup(path));
+    println!("Restored from {}", path.display());
+}
@@ -0,0 +1,53 @@
+use crate::action::fill::{fill_command, placeholder_values};
+use crate::cli::exit::or_exit;
+use docu::database::data_types::UsageKind;
+use docu::store::scriptlet_store::ScriptletStore;
+use std::io::{IsTerminal, Write, stdout};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Copies the command of a scriptlet to the clipboard. Terminals are asked to do this with an
+/// OSC 52 escape sequence, which also works over SSH. When the output is not a terminal the
+/// command is printed instead, so it can be piped into a clipboard tool. Placeholders are filled
+/// in first.
+pub fn copy_scriptlet(store: &dyn ScriptletStore, id: i64) {
+    let Some(scriptlet) = or_exit(store.get(id)) else {
+        println!("No scriptlet found with id {id}");
+        return;
+    };
+    let command = fill_command(&scriptlet.command, &placeholder_values(&scriptlet));
+    let mut stdout = stdout();
+    if stdout.is_terminal() {
+        write!(stdout, "\x1b]52;c;{}\x07", base64(&command))
+            .and_then(|_| stdout.flush())
+            .expect("Failed to write to terminal");
+        println!("Copied {} to the clipboard", scriptlet.name);
+    } else {
+        println!("{command}");
+    }
+    if let Err(err) = store.record_usage(id, UsageKind::Copy) {
+        eprintln!("Usage not recorded: {err}");
+    }
+}
+
+fn base64(text: &str) -> String {
+    let mut encoded = String::new();
+    for chunk in text.as_bytes().chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (triple >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,141 @@
+use dialoguer::console::Style;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Input, Select};
+use docu::database::data_types::{PlaceholderData, PlaceholderType, ScriptletData};
+use std::env;
+use std::io::{IsTerminal, stderr, stdin};
+
+/// The value to use for each placeholder of a scriptlet. A value set in the environment is used
+/// as it is. Otherwise the user is asked, starting from the default, or the default is used when
+/// there is no terminal to ask on. Placeholders left without a value are skipped, so the shell
+/// fills them in as usual.
+pub fn placeholder_values(scriptlet: &ScriptletData) -> Vec<(String, String)> {
+    let interactive = stdin().is_terminal() && stderr().is_terminal();
+    scriptlet
+        .placeholders
+        .iter()
+        .filter(|placeholder| placeholder.used_in(&scriptlet.command))
+        .filter_map(|placeholder| {
+            let value = env::var(&placeholder.name)
+                .ok()
+                .or_else(|| match interactive {
+                    true => Some(prompt_value(placeholder)),
+                    false => placeholder.default.clone(),
+                })?;
+            Some((placeholder.name.clone(), value))
+        })
+        .collect()
+}
+
+fn prompt_value(placeholder: &PlaceholderData) -> String {
+    let prompt = match &placeholder.description {
+        Some(description) => format!("${} ({description})", placeholder.name),
+        None => format!("${}", placeholder.name),
+    };
+    if placeholder.kind == PlaceholderType::Enum && !placeholder.choices.is_empty() {
+        let default = placeholder
+            .default
+            .as_ref()
+            .and_then(|default| placeholder.choices.iter().position(|c| c == default))
+            .unwrap_or(0);
+        let choice = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(&placeholder.choices)
+            .default(default)
+            .interact()
+            .expect("Failed to read selection");
+        return placeholder.choices[choice].clone();
+    }
+    let theme = ColorfulTheme::default();
+    let mut input = Input::<String>::with_theme(&theme)
+        .with_prompt(prompt)
+        .validate_with(|value: &String| {
+            placeholder
+                .validate(value)
+                .map_err(|reason| Style::new().red().apply_to(reason).to_string())
+        });
+    if let Some(default) = &placeholder.default {
+        input = input.default(default.clone());
+    }
+    input.interact_text().expect("Failed to read input")
+}
+
+/// Replaces the placeholders that were given a value with the value itself, quoted so the shell
+/// reads it as it is. Placeholders inside single quotes aren't expanded by the shell, so they are
+/// left alone too.
+pub fn fill_command(command: &str, values: &[(String, String)]) -> String {
+    let mut filled = String::with_capacity(command.len());
+    let mut single_quoted = false;
+    let mut double_quoted = false;
+    let mut rest = command;
+    while let Some(c) = rest.chars().next() {
+        if c == '$'
+            && !single_quoted
+            && let Some((len, value)) = variable_at(rest, values)
+        {
+            if double_quoted {
+                filled.push_str(&escape_double_quoted(value));
+            } else {
+                filled.push_str(&shell_quote(value));
+            }
+            rest = &rest[len..];
+            continue;
+        }
+        let mut len = c.len_utf8();
+        match c {
+            '\\' if !single_quoted => {
+                len += rest[len..].chars().next().map_or(0, char::len_utf8);
+            }
+            '\'' if !double_quoted => single_quoted = !single_quoted,
+            '"' if !single_quoted => double_quoted = !double_quoted,
+            _ => {}
+        }
+        filled.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    filled
+}
+
+/// The length of the `$NAME` or `${NAME}` at the start of `text` and its value, if it is one of
+/// the placeholders given a value.
+fn variable_at<'v>(text: &str, values: &'v [(String, String)]) -> Option<(usize, &'v str)> {
+    let (name, len) = match text[1..].strip_prefix('{') {
+        Some(braced) => {
+            let name = &braced[..braced.find('}')?];
+            (name, name.len() + 3)
+        }
+        None => {
+            let name_len = text[1..]
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(text.len() - 1);
+            (&text[1..1 + name_len], name_len + 1)
+        }
+    };
+    values
+        .iter()
+        .find(|(placeholder, _)| placeholder == name)
+        .map(|(_, value)| (len, value.as_str()))
+}
+
+fn shell_quote(value: &str) -> String {
+    let safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_./:@%+=,~-".contains(c));
+    if safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+fn escape_double_quoted(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | '$' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
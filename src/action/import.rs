@@ -0,0 +1,97 @@
+use crate::import::history::{HistoryEntry, HistorySource, import_candidates, read_history};
+use crate::parse::parser::{AddOptions, parse_scriptlet};
+use crate::tui::print::show_import_progress_tui;
+use crate::tui::select::select_commands_tui;
+use dialoguer::console::Term;
+use docu::store::scriptlet_store::ScriptletStore;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+/// Offers the commands in a history for import, and adds the ones picked as scriptlets, asking
+/// for each one's name and description as `docu add` does. Where and when each command was first
+/// run is kept if the history records it. Without a source or file, every history found in its
+/// usual place is read, except that the shells' own files are skipped if an Atuin or McFly
+/// database is found, as it holds the same commands.
+pub fn import_history(
+    store: &dyn ScriptletStore,
+    source: Option<HistorySource>,
+    file: Option<&Path>,
+    limit: usize,
+) {
+    if !Term::stderr().is_term() {
+        eprintln!("Importing history needs a terminal to pick the commands on");
+        exit(1);
+    }
+    let files: Vec<(Option<HistorySource>, PathBuf)> = match (source, file) {
+        (source, Some(file)) => vec![(source, file.to_path_buf())],
+        (Some(source), None) => source
+            .default_path()
+            .map(|path| (Some(source), path))
+            .into_iter()
+            .collect(),
+        (None, None) => {
+            let mut found: Vec<(Option<HistorySource>, PathBuf)> = HistorySource::ALL
+                .iter()
+                .filter_map(|source| source.default_path().map(|path| (Some(*source), path)))
+                .filter(|(_, path)| path.is_file())
+                .collect();
+            if found
+                .iter()
+                .any(|(source, _)| source.is_some_and(|s| s.is_database()))
+            {
+                found.retain(|(source, _)| source.is_some_and(|s| s.is_database()));
+            }
+            found
+        }
+    };
+    if files.is_empty() {
+        eprintln!("No shell history found, pass the history file to read with --file");
+        exit(1);
+    }
+    let mut entries = Vec::new();
+    for (source, path) in files {
+        match read_file(source, &path) {
+            Ok(read) => entries.extend(read),
+            Err(err) => {
+                eprintln!("Could not read {}: {err}", path.display());
+                exit(1);
+            }
+        }
+    }
+    let mut candidates = import_candidates(entries);
+    candidates.truncate(limit);
+    if candidates.is_empty() {
+        println!("No commands worth importing found");
+        return;
+    }
+    let chosen = select_commands_tui(&candidates);
+    for (i, candidate) in chosen.iter().enumerate() {
+        show_import_progress_tui(i + 1, chosen.len(), &candidate.entry.command);
+        let entry = &candidate.entry;
+        let options = AddOptions {
+            cwd: entry.cwd.clone(),
+            host: entry.host.clone(),
+            created_at: entry.time,
+            ..AddOptions::default()
+        };
+        if let Err(err) = parse_scriptlet(store, &entry.command, options) {
+            eprintln!("{err}");
+            exit(1);
+        }
+    }
+}
+
+fn read_file(source: Option<HistorySource>, path: &Path) -> io::Result<Vec<HistoryEntry>> {
+    let source = match source {
+        Some(source) => source,
+        None => {
+            let mut start = Vec::new();
+            File::open(path)?.take(16).read_to_end(&mut start)?;
+            HistorySource::guess(path, &start)
+        }
+    };
+    read_history(source, path)
+}
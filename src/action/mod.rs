@@ -0,0 +1,5 @@
+pub mod backup;
+pub mod copy;
+pub mod fill;
+pub mod import;
+pub mod run;
@@ -0,0 +1,28 @@
+use crate::action::fill::placeholder_values;
+use crate::cli::exit::or_exit;
+use docu::database::data_types::UsageKind;
+use docu::store::scriptlet_store::ScriptletStore;
+use std::env;
+use std::process::{Command, exit};
+
+/// Runs the command of a scriptlet in the user's shell. Placeholders such as `$FILE` are ordinary
+/// shell variables, so they are taken from the environment when set there, and otherwise asked
+/// for or given their defaults.
+pub fn run_scriptlet(store: &dyn ScriptletStore, id: i64) {
+    let Some(scriptlet) = or_exit(store.get(id)) else {
+        println!("No scriptlet found with id {id}");
+        return;
+    };
+    if let Err(err) = store.record_usage(id, UsageKind::Run) {
+        eprintln!("Usage not recorded: {err}");
+    }
+    let values = placeholder_values(&scriptlet);
+    let shell = env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+    let status = Command::new(shell)
+        .arg("-c")
+        .arg(&scriptlet.command)
+        .envs(values)
+        .status()
+        .expect("Failed to run scriptlet");
+    exit(status.code().unwrap_or(1));
+}
@@ -1,4 +1,11 @@
-use clap::{Parser, Subcommand};
+use crate::cli::duration::{parse_duration, parse_time};
+use crate::import::history::HistorySource;
+use crate::parse::conflicts::ConflictStrategy;
+use crate::parse::similarity::DUPLICATE_THRESHOLD;
+use clap::{Parser, Subcommand, ValueEnum};
+use docu::database::data_types::SortOrder;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Parser)]
 #[command(
@@ -9,6 +16,15 @@ use clap::{Parser, Subcommand};
     after_help = "For more details, visit https://github.com/Magic-JD/docu"
 )]
 pub struct Cli {
+    /// Use the database at this path instead of the default one. Can also be set with the DOCU_DB
+    /// environment variable.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub db: Option<PathBuf>,
+
+    /// Use a named library from the [libraries] table in config.toml.
+    #[arg(long, global = true, value_name = "NAME", conflicts_with = "db")]
+    pub library: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -19,31 +35,370 @@ pub enum Commands {
     Add {
         /// The full command invocation to be documented.
         /// It should be wrapped in quotes, and any internal quotation marks or $ should be
-        /// escaped.
-        #[arg(required = true)]
-        args: String,
+        /// escaped. If omitted, the command is read from --file or from stdin.
+        args: Option<String>,
+
+        /// Read the command from this file instead, keeping its newlines and indentation. Use -
+        /// to read from stdin.
+        #[arg(long, value_name = "PATH", conflicts_with = "args")]
+        file: Option<PathBuf>,
+
+        /// Read the command from stdin, the same as --file -.
+        #[arg(long, conflicts_with_all = ["args", "file"])]
+        stdin: bool,
+
+        /// The name of the scriptlet. If omitted you will be asked.
+        #[arg(long)]
+        name: Option<String>,
+
+        /// What the scriptlet does. If omitted you will be asked. When both --name and
+        /// --description are given nothing is asked, so docu add can run from scripts.
+        #[arg(long)]
+        description: Option<String>,
+
+        /// A tool the scriptlet uses, instead of the tools detected in the command. Can be given
+        /// more than once.
+        #[arg(long = "tool", value_name = "TOOL")]
+        tools: Vec<String>,
+
+        /// A tag for the scriptlet. Can be given more than once.
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Keep the command exactly as given, without replacing values with placeholders.
+        #[arg(long)]
+        no_placeholders: bool,
+
+        /// What to do if the name is already used by another scriptlet. If omitted you will be
+        /// asked.
+        #[arg(long, value_enum)]
+        on_conflict: Option<OnConflict>,
+
+        /// The page the scriptlet came from, e.g. the documentation or answer it was copied from.
+        #[arg(long, value_name = "URL")]
+        source: Option<String>,
     },
 
     /// Display saved scriptlets. You can either display all scriptlets or filter them by a specific tool.
     Show {
         /// The specific tool to show scriptlets for. If omitted, all scriptlets for all tools are shown.
         tool: Option<String>,
+
+        /// Only show scriptlets with this tag.
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only show scriptlets added since this date (e.g. 2025-03-01) or this long ago (e.g. 2w).
+        #[arg(long, value_parser = parse_time)]
+        since: Option<SystemTime>,
+
+        /// Only show scriptlets added before this date (e.g. 2025-03-01) or this long ago (e.g. 2w).
+        #[arg(long, value_parser = parse_time)]
+        before: Option<SystemTime>,
+
+        /// The order to show scriptlets in.
+        #[arg(long, value_enum, default_value_t = Sort::Recent)]
+        sort: Sort,
+
+        /// Pick one of the scriptlets interactively and print its command.
+        #[arg(long)]
+        select: bool,
     },
 
     /// Search for scriptlets by keywords.
     Search {
         /// The keyword(s) to search for in the scriptlet documentation.
         search: Vec<String>,
+
+        /// The order to show results in. By default results are ordered by relevance, and
+        /// frecency blends relevance with how often and recently each scriptlet was used.
+        #[arg(long, value_enum)]
+        sort: Option<Sort>,
+
+        /// Only show results added since this date (e.g. 2025-03-01) or this long ago (e.g. 2w).
+        #[arg(long, value_parser = parse_time)]
+        since: Option<SystemTime>,
+
+        /// Only show results added before this date (e.g. 2025-03-01) or this long ago (e.g. 2w).
+        #[arg(long, value_parser = parse_time)]
+        before: Option<SystemTime>,
+
+        /// Pick one of the results interactively and print its command.
+        #[arg(long)]
+        select: bool,
+    },
+
+    /// Copy the command of a scriptlet to the clipboard, after asking for the values of its
+    /// placeholders.
+    Copy {
+        /// The ID of the scriptlet to copy.
+        id: i64,
+    },
+
+    /// Run the command of a scriptlet in your shell. Placeholders such as $FILE are read from
+    /// the environment, e.g. `FILE=data.csv docu run 3`, and otherwise asked for.
+    Run {
+        /// The ID of the scriptlet to run.
+        id: i64,
+    },
+
+    /// Edit an existing scriptlet in your $EDITOR. The name, description and command can all be
+    /// changed, while the ID is kept.
+    Edit {
+        /// The ID of the scriptlet to edit.
+        id: i64,
+    },
+
+    /// Show the previous revisions of a scriptlet, with the changes made in each one.
+    History {
+        /// The ID of the scriptlet to show the history of.
+        id: i64,
+    },
+
+    /// Restore a scriptlet to one of its previous revisions. The current version is kept as a
+    /// new revision, so a revert can itself be reverted.
+    Revert {
+        /// The ID of the scriptlet to revert.
+        id: i64,
+        /// The revision to restore, as shown by the `history` command.
+        revision: i64,
     },
 
     /// Remove one or more scriptlets by their ID. You can get the ID of
-    /// a scriptlet by using the `show` or `search` command.
+    /// a scriptlet by using the `show` or `search` command. Removed scriptlets are
+    /// moved to the trash, where they can be restored until the trash is emptied.
     Remove {
         /// The ID(s) of the scriptlet(s) to remove.
         ids: Vec<i64>,
     },
 
+    /// View, restore or permanently delete removed scriptlets.
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+
+    /// List every tool with how many scriptlets use it and when it was last used, or tidy up the
+    /// tools with one of the subcommands.
+    Tools {
+        #[command(subcommand)]
+        command: Option<ToolsCommands>,
+    },
+
+    /// Pick commands from your shell history, or from an Atuin or McFly database, and add them as
+    /// scriptlets. The most often run commands are listed first, each once, without trivial
+    /// commands such as `ls` or `cd`.
+    ImportHistory {
+        /// The history to read. If omitted, every history found is read, or only the Atuin and
+        /// McFly databases if there are any.
+        #[arg(long, value_enum)]
+        from: Option<ImportFrom>,
+
+        /// Read this history file instead of the usual one. What wrote it is worked out from the
+        /// file if --from isn't given.
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+
+        /// The most commands to list.
+        #[arg(long, default_value_t = 200)]
+        limit: usize,
+    },
+
+    /// Scan the whole library for scriptlets with the same or nearly the same command. Commands
+    /// are compared after normalizing whitespace, quoting style and placeholder names.
+    Dedupe {
+        /// How similar two commands must be to be reported, from 0.0 to 1.0.
+        #[arg(long, default_value_t = DUPLICATE_THRESHOLD)]
+        threshold: f64,
+    },
+
+    /// Manage the tags attached to a scriptlet. Tags are your own categories, kept separate from
+    /// the tools that are detected automatically.
+    Tag {
+        #[command(subcommand)]
+        command: TagCommands,
+    },
+
+    /// Back up the database to a file. This is safe to run while docu is being used elsewhere.
+    Backup {
+        /// The file to write the backup to. It must not exist yet.
+        file: PathBuf,
+    },
+
+    /// Replace the database with a backup. A copy of the current database is saved next to it
+    /// first, and older backups are upgraded to the current schema.
+    Restore {
+        /// The backup file to restore.
+        file: PathBuf,
+
+        /// Don't ask for confirmation.
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Check the database for damage, a full text search index that no longer matches the
+    /// scriptlets, and broken tool links, and offer to repair the index and links.
+    Doctor {
+        /// Repair without asking for confirmation.
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Manage the docu database.
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
+    },
+
     /// Generate a default configuration file. This command creates a `docu.toml` file in the application's
     /// default configuration directory, pre-populated with default settings.
     GenerateConfig,
 }
+
+impl Commands {
+    /// Whether the command only needs what every `ScriptletStore` provides, and so also works
+    /// on a Markdown library. The others maintain the SQLite database file itself.
+    pub fn supports_markdown_library(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Backup { .. }
+                | Commands::Restore { .. }
+                | Commands::Doctor { .. }
+                | Commands::Db { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Sort {
+    /// Most recently added first.
+    Recent,
+    /// Most frequently and recently used first.
+    Frecency,
+}
+
+impl From<Sort> for SortOrder {
+    fn from(sort: Sort) -> Self {
+        match sort {
+            Sort::Recent => SortOrder::Recent,
+            Sort::Frecency => SortOrder::Frecency,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum OnConflict {
+    /// Don't add the scriptlet.
+    Fail,
+    /// Replace the existing scriptlet. The old version is kept in its history.
+    Overwrite,
+    /// Add a numeric suffix to the new name, e.g. name-2.
+    Suffix,
+}
+
+impl From<OnConflict> for ConflictStrategy {
+    fn from(on_conflict: OnConflict) -> Self {
+        match on_conflict {
+            OnConflict::Fail => ConflictStrategy::Fail,
+            OnConflict::Overwrite => ConflictStrategy::Overwrite,
+            OnConflict::Suffix => ConflictStrategy::Suffix,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ImportFrom {
+    Bash,
+    Zsh,
+    Fish,
+    Atuin,
+    #[value(name = "mcfly")]
+    McFly,
+}
+
+impl From<ImportFrom> for HistorySource {
+    fn from(from: ImportFrom) -> Self {
+        match from {
+            ImportFrom::Bash => HistorySource::Bash,
+            ImportFrom::Zsh => HistorySource::Zsh,
+            ImportFrom::Fish => HistorySource::Fish,
+            ImportFrom::Atuin => HistorySource::Atuin,
+            ImportFrom::McFly => HistorySource::McFly,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ToolsCommands {
+    /// Delete tools that are no longer used by any scriptlet.
+    Prune,
+
+    /// Merge one tool into another, moving all of its scriptlets across.
+    Merge {
+        /// The tool to merge away.
+        from: String,
+        /// The tool to merge into. It is created if it doesn't exist yet.
+        into: String,
+    },
+
+    /// Rename a tool.
+    Rename {
+        /// The current name of the tool.
+        from: String,
+        /// The new name of the tool.
+        to: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TagCommands {
+    /// Add one or more tags to a scriptlet.
+    Add {
+        /// The ID of the scriptlet to tag.
+        id: i64,
+        /// The tag(s) to add.
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// Remove one or more tags from a scriptlet.
+    Remove {
+        /// The ID of the scriptlet to untag.
+        id: i64,
+        /// The tag(s) to remove.
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TrashCommands {
+    /// List the scriptlets in the trash.
+    List,
+
+    /// Restore one or more scriptlets from the trash.
+    Restore {
+        /// The ID(s) of the scriptlet(s) to restore.
+        #[arg(required = true)]
+        ids: Vec<i64>,
+    },
+
+    /// Permanently delete scriptlets in the trash.
+    Empty {
+        /// Only delete scriptlets that have been in the trash for at least this long, e.g. 30d.
+        /// Supported units are s, m, h, d and w.
+        #[arg(long, value_parser = parse_duration)]
+        older_than: Option<Duration>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommands {
+    /// Apply any pending schema migrations. Migrations are also applied automatically whenever
+    /// docu opens the database, and a backup of the database is taken before any are applied.
+    Migrate {
+        /// Show the applied and pending migrations without applying them.
+        #[arg(long)]
+        status: bool,
+    },
+}
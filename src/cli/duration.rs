@@ -0,0 +1,73 @@
+use docu::database::timestamp::parse_timestamp;
+use std::time::{Duration, SystemTime};
+
+/// Parses a duration such as `30d`, `2w` or `12h`. Supported units are seconds (`s`), minutes
+/// (`m`), hours (`h`), days (`d`) and weeks (`w`).
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("missing unit in '{value}', e.g. 30d"))?;
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid amount in '{value}'"))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(format!("unknown unit '{unit}', use one of s, m, h, d or w")),
+    };
+    let seconds = amount
+        .checked_mul(seconds)
+        .ok_or_else(|| format!("duration too large in '{value}'"))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Parses one end of a time range: either a UTC date such as `2025-03-01`, optionally followed by
+/// a time such as `14:30`, or a duration such as `3d` meaning that long ago.
+pub fn parse_time(value: &str) -> Result<SystemTime, String> {
+    if let Some(time) = parse_timestamp(value) {
+        return Ok(time);
+    }
+    let ago = parse_duration(value).map_err(|err| {
+        if err.starts_with("duration too large") {
+            err
+        } else {
+            format!("'{value}' is neither a date like 2025-03-01 nor a duration like 3d")
+        }
+    })?;
+    SystemTime::now()
+        .checked_sub(ago)
+        .ok_or_else(|| format!("'{value}' is too long ago"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(
+            parse_duration("2w"),
+            Ok(Duration::from_secs(2 * 7 * 24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn rejects_durations_too_large_to_represent() {
+        let err = parse_duration("999999999999999999w").unwrap_err();
+        assert!(err.starts_with("duration too large"), "{err}");
+        let err = parse_time("999999999999999999w").unwrap_err();
+        assert!(err.starts_with("duration too large"), "{err}");
+    }
+
+    #[test]
+    fn rejects_durations_reaching_before_the_epoch() {
+        let err = parse_time("20000000000000w").unwrap_err();
+        assert!(err.ends_with("is too long ago"), "{err}");
+    }
+}
@@ -0,0 +1,14 @@
+use docu::errors::error::DocuError;
+use std::process::exit;
+
+/// The value of a store operation that succeeded. Otherwise the error is reported and docu exits,
+/// e.g. when another docu process held the database for longer than the busy timeout.
+pub fn or_exit<T>(result: Result<T, DocuError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => {
+            eprintln!("{err}");
+            exit(1);
+        }
+    }
+}
@@ -1 +1,3 @@
 pub mod command;
+pub mod duration;
+pub mod exit;
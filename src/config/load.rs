@@ -1,6 +1,7 @@
 use super::theme::themes;
 use crate::config::DEFAULT_CONFIG;
 use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::LazyLock;
@@ -11,6 +12,32 @@ pub static CONFIG: LazyLock<Config> = LazyLock::new(get_config);
 pub struct Config {
     #[serde(default)]
     pub colors: ColorConfig,
+    #[serde(default)]
+    pub libraries: HashMap<String, PathBuf>,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    #[serde(default)]
+    pub parser: ParserConfig,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct ParserConfig {
+    pub wrappers: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct DatabaseConfig {
+    pub busy_timeout_ms: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct BackupConfig {
+    pub enabled: Option<bool>,
+    pub directory: Option<PathBuf>,
+    pub keep: Option<usize>,
+    pub interval_hours: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize, Default, Clone)]
@@ -134,6 +161,20 @@ fn get_config() -> Config {
         config.colors.theme = theme;
         config.colors.scriptlet_name = scriptlet_name;
         config.colors.scriptlet_description = scriptlet_description;
+        config.libraries.extend(user_config.libraries);
+
+        let backup = user_config.backup;
+        config.backup.enabled = backup.enabled.or(config.backup.enabled);
+        config.backup.directory = backup.directory.or(config.backup.directory);
+        config.backup.keep = backup.keep.or(config.backup.keep);
+        config.backup.interval_hours = backup.interval_hours.or(config.backup.interval_hours);
+
+        config.database.busy_timeout_ms = user_config
+            .database
+            .busy_timeout_ms
+            .or(config.database.busy_timeout_ms);
+
+        config.parser.wrappers = user_config.parser.wrappers.or(config.parser.wrappers);
     }
 
     let theme_name = config.colors.theme.clone().unwrap_or("default".to_string());
@@ -0,0 +1,126 @@
+use crate::config::load::CONFIG;
+use crate::database::location::expand_home;
+use crate::database::migrate::{latest_version, schema_version};
+use crate::errors::error::DocuError;
+use crate::errors::error::DocuError::Backup;
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, MAIN_DB, OpenFlags};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_KEEP: usize = 5;
+const DEFAULT_INTERVAL_HOURS: u64 = 24;
+
+/// Copies the database to `path` with SQLite's online backup API, which copies a consistent
+/// snapshot even while other docu processes are using the database.
+pub(crate) fn backup_to(conn: &Connection, path: &Path) -> Result<(), DocuError> {
+    if path.exists() {
+        return Err(Backup(format!("{} already exists", path.display())));
+    }
+    conn.backup(MAIN_DB, path, None)?;
+    Ok(())
+}
+
+/// Checks that `path` holds a docu database this version can read, returning its schema
+/// version.
+pub(crate) fn check_restorable(path: &Path) -> Result<i64, DocuError> {
+    if !path.is_file() {
+        return Err(Backup(format!("{} does not exist", path.display())));
+    }
+    let source = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let integrity: String = source.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        return Err(Backup(format!(
+            "{} failed its integrity check: {integrity}",
+            path.display()
+        )));
+    }
+    let has_scriptlets: bool = source.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'scriptlet'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_scriptlets {
+        return Err(Backup(format!("{} is not a docu database", path.display())));
+    }
+    let version = schema_version(&source)?;
+    if version > latest_version() {
+        return Err(Backup(format!(
+            "{} has schema version {version}, but this docu only supports up to version {}",
+            path.display(),
+            latest_version()
+        )));
+    }
+    Ok(version)
+}
+
+/// Replaces the contents of the database with the backup at `path`.
+pub(crate) fn restore_from(conn: &mut Connection, path: &Path) -> Result<(), DocuError> {
+    conn.restore(MAIN_DB, path, None::<fn(Progress)>)?;
+    Ok(())
+}
+
+/// Takes a backup into the configured backup directory if automatic backups are enabled and
+/// the newest one is older than the configured interval, then deletes the oldest backups
+/// beyond the number to keep.
+pub(crate) fn rotate_backups(conn: &Connection, database_path: &Path) -> Result<(), DocuError> {
+    let config = &CONFIG.backup;
+    if !config.enabled.unwrap_or(false) {
+        return Ok(());
+    }
+    let directory = config
+        .directory
+        .as_deref()
+        .map(expand_home)
+        .unwrap_or_else(|| database_path.with_file_name("backups"));
+    fs::create_dir_all(&directory)?;
+    let prefix = format!(
+        "{}-",
+        database_path
+            .file_stem()
+            .map_or("docu".into(), |stem| stem.to_string_lossy())
+    );
+    let mut backups = existing_backups(&directory, &prefix)?;
+
+    let interval =
+        Duration::from_secs(config.interval_hours.unwrap_or(DEFAULT_INTERVAL_HOURS) * 60 * 60);
+    let newest_age = backups
+        .last()
+        .and_then(|newest| fs::metadata(newest).ok()?.modified().ok())
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    if newest_age.is_none_or(|age| age >= interval) {
+        let timestamp: String =
+            conn.query_row("SELECT strftime('%Y%m%d-%H%M%S', 'now')", [], |row| {
+                row.get(0)
+            })?;
+        let path = directory.join(format!("{prefix}{timestamp}.db"));
+        if !path.exists() {
+            backup_to(conn, &path)?;
+            backups.push(path);
+        }
+    }
+
+    let keep = config.keep.unwrap_or(DEFAULT_KEEP).max(1);
+    let excess = backups.len().saturating_sub(keep);
+    for old in &backups[..excess] {
+        fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+/// Backups in `directory` made for this database, oldest first. The timestamp in their names
+/// sorts chronologically.
+fn existing_backups(directory: &Path, prefix: &str) -> Result<Vec<PathBuf>, DocuError> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(directory)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy())
+                .is_some_and(|name| name.starts_with(prefix) && name.ends_with(".db"))
+        })
+        .collect();
+    backups.sort();
+    Ok(backups)
+}
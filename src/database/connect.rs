@@ -1,119 +1,365 @@
-use crate::database::data_types::ScriptletData;
-use crate::database::scriptlet::{match_scriptlets, remove_scriptlet};
-use crate::database::{scriptlet, tool, tool_to_scriptlet};
+use crate::config::load::CONFIG;
+use crate::database::data_types::{
+    DoctorReport, NewScriptlet, RevisionData, ScriptletData, ScriptletFilter, SortOrder, TimeRange,
+    ToolData, TrashedScriptletData, UsageKind,
+};
+use crate::database::location::database_path;
+use crate::database::migrate::{Migration, MigrationStatus};
+use crate::database::scriptlet::{get_scriptlet, match_scriptlets, remove_scriptlet};
+use crate::database::{
+    backup, doctor, migrate, placeholder, revision, scriptlet, tag, tag_to_scriptlet, tool,
+    tool_to_scriptlet, usage,
+};
 use crate::errors::error::DocuError;
-use crate::errors::error::DocuError::{Access, DatabaseSql};
-use dirs::data_dir;
-use rusqlite::{Connection, Error};
-use std::fs::create_dir_all;
-use std::path::PathBuf;
-use std::sync::{LazyLock, Mutex, MutexGuard};
-
-static CONNECTION: LazyLock<Mutex<Connection>> = LazyLock::new(|| {
-    let conn = Connection::open(database_path()).expect("Failed to open database");
-    conn.execute_batch(
-        "
-        BEGIN;
-        CREATE TABLE IF NOT EXISTS scriptlet (
-            id          INTEGER PRIMARY KEY AUTOINCREMENT,
-            name        TEXT NOT NULL UNIQUE,
-            command     TEXT NOT NULL,
-            description TEXT,
-            time        DATETIME DEFAULT CURRENT_TIMESTAMP
-        );
-        CREATE TABLE IF NOT EXISTS tool (
-            id   INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL UNIQUE,
-            time DATETIME DEFAULT CURRENT_TIMESTAMP
-        );
-        CREATE TABLE IF NOT EXISTS tool_scriptlet (
-            id            INTEGER PRIMARY KEY AUTOINCREMENT,
-            tool_id       INTEGER NOT NULL,
-            scriptlet_id  INTEGER NOT NULL,
-            UNIQUE(tool_id, scriptlet_id),
-            FOREIGN KEY(tool_id)      REFERENCES tool(id) ON DELETE CASCADE,
-            FOREIGN KEY(scriptlet_id) REFERENCES scriptlet(id) ON DELETE CASCADE
-        );
-        CREATE VIRTUAL TABLE IF NOT EXISTS scriptlet_fts
-        USING fts5(
-            name,
-            description,
-            command,
-            tokenize='porter',
-            content='scriptlet',
-            content_rowid='id'
-        );
-        CREATE TRIGGER IF NOT EXISTS scriptlet_ai AFTER INSERT ON scriptlet BEGIN
-          INSERT INTO scriptlet_fts(rowid, name, description, command)
-            VALUES (new.id, new.name, new.description, new.command);
-        END;
-        CREATE TRIGGER IF NOT EXISTS scriptlet_ad AFTER DELETE ON scriptlet BEGIN
-          INSERT INTO scriptlet_fts(scriptlet_fts, rowid, name, description, command)
-            VALUES('delete', old.id, old.name, old.description, old.command);
-        END;
-        CREATE TRIGGER IF NOT EXISTS scriptlet_au AFTER UPDATE ON scriptlet BEGIN
-          INSERT INTO scriptlet_fts(scriptlet_fts, rowid, name, description, command)
-            VALUES('delete', old.id, old.name, old.description, old.command);
-          INSERT INTO scriptlet_fts(rowid, name, description, command)
-            VALUES (new.id, new.name, new.description, new.command);
-        END;
-        COMMIT;
-        ",
-    )
-    .expect("Failed to initialize database");
-    Mutex::new(conn)
-});
+use crate::errors::error::DocuError::{Access, Tool};
+use rusqlite::{Connection, OpenFlags};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5000;
+
+static CONNECTION: OnceLock<Mutex<Connection>> = OnceLock::new();
+static READ_CONNECTION: OnceLock<Mutex<Connection>> = OnceLock::new();
+
+/// Opens the database for writing, in WAL mode so that other docu processes can keep reading
+/// while this one writes.
+fn open_database() -> Result<Connection, DocuError> {
+    let conn = Connection::open(sqlite_path()?)?;
+    conn.busy_timeout(busy_timeout())?;
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+    Ok(conn)
+}
+
+fn open_writable() -> Result<Connection, DocuError> {
+    let mut conn = open_database()?;
+    migrate::migrate(&mut conn, &database_path())?;
+    rotate_backups(&conn);
+    Ok(conn)
+}
+
+/// Opens the database for reading only. A database that doesn't exist yet, still needs migrating
+/// or isn't in WAL mode yet is first opened for writing so it can be brought up to date.
+fn open_read_only() -> Result<Connection, DocuError> {
+    if !sqlite_path()?.exists() {
+        drop(get_conn()?);
+    }
+    let conn = Connection::open_with_flags(
+        database_path(),
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    conn.busy_timeout(busy_timeout())?;
+    let journal_mode: String = conn.pragma_query_value(None, "journal_mode", |row| row.get(0))?;
+    if migrate::schema_version(&conn)? == migrate::latest_version() && journal_mode == "wal" {
+        rotate_backups(&conn);
+    } else {
+        drop(get_conn()?);
+    }
+    Ok(conn)
+}
 
-fn database_path() -> PathBuf {
-    let mut path = data_dir().expect("Failed to obtain data directory");
-    path.push("docu");
-    create_dir_all(&path).expect("Failed to create data directory");
-    path.push("docu.db");
-    path
+/// The path of the database, which must not be a Markdown library as those are only supported
+/// through the `ScriptletStore` trait.
+fn sqlite_path() -> Result<PathBuf, DocuError> {
+    let path = database_path();
+    if path.is_dir() {
+        return Err(Access(format!(
+            "{} is a Markdown library, which doesn't support this command",
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+fn rotate_backups(conn: &Connection) {
+    if let Err(err) = backup::rotate_backups(conn, &database_path()) {
+        eprintln!("Automatic backup failed: {err}");
+    }
+}
+
+fn busy_timeout() -> Duration {
+    Duration::from_millis(
+        CONFIG
+            .database
+            .busy_timeout_ms
+            .unwrap_or(DEFAULT_BUSY_TIMEOUT_MS),
+    )
 }
 
 fn get_conn() -> Result<MutexGuard<'static, Connection>, DocuError> {
-    CONNECTION.lock().map_err(|e| Access(e.to_string()))
+    lock_connection(&CONNECTION, open_writable)
+}
+
+fn get_read_conn() -> Result<MutexGuard<'static, Connection>, DocuError> {
+    lock_connection(&READ_CONNECTION, open_read_only)
+}
+
+fn lock_connection(
+    connection: &'static OnceLock<Mutex<Connection>>,
+    open: fn() -> Result<Connection, DocuError>,
+) -> Result<MutexGuard<'static, Connection>, DocuError> {
+    let mutex = match connection.get() {
+        Some(mutex) => mutex,
+        None => {
+            let conn = open()?;
+            connection.get_or_init(|| Mutex::new(conn))
+        }
+    };
+    mutex.lock().map_err(|e| Access(e.to_string()))
 }
 
-pub fn add_scriptlet(
-    title: &str,
-    tools: Vec<&str>,
-    command: &str,
-    description: &str,
-) -> Result<(), DocuError> {
+/// Adds a new scriptlet, returning its id.
+pub fn add_scriptlet(scriptlet: &NewScriptlet) -> Result<i64, DocuError> {
     let conn = get_conn()?;
-    let scriptlet_idx = scriptlet::insert_row(title, command, description, &conn)?;
+    let tx = conn.unchecked_transaction()?;
+    let scriptlet_idx = scriptlet::insert_row(scriptlet, &conn)?;
+    link_tools(scriptlet_idx, &scriptlet.tools, &conn)?;
+    link_tags(scriptlet_idx, &scriptlet.tags, &conn)?;
+    placeholder::insert_placeholders(scriptlet_idx, &scriptlet.placeholders, &conn)?;
+    tx.commit()?;
+    Ok(scriptlet_idx)
+}
+
+/// Replaces an existing scriptlet, which may be in the trash, keeping the replaced version as a
+/// revision. The tools are replaced if the command changed, and any tags are added to the old
+/// ones. The placeholders are replaced if any are given, otherwise the ones the new command still
+/// uses are kept.
+pub fn update_scriptlet(id: i64, scriptlet: &NewScriptlet) -> Result<(), DocuError> {
+    let conn = get_conn()?;
+    let tx = conn.unchecked_transaction()?;
+    scriptlet::restore_row(id, &conn)?;
+    let old = get_scriptlet(id, &conn)?;
+    if let Some(old) = &old {
+        revision::insert_revision(old, &conn)?;
+    }
+    let command_changed = old.is_some_and(|old| old.command != scriptlet.command);
+    scriptlet::update_row(id, scriptlet, &conn)?;
+    if command_changed {
+        tool_to_scriptlet::unlink_scriptlet(id, &conn)?;
+        link_tools(id, &scriptlet.tools, &conn)?;
+    }
+    link_tags(id, &scriptlet.tags, &conn)?;
+    placeholder::replace_placeholders(id, &scriptlet.command, &scriptlet.placeholders, &conn)?;
+    tx.commit()?;
+    Ok(())
+}
+
+fn link_tools(id: i64, tools: &[String], conn: &MutexGuard<Connection>) -> Result<(), DocuError> {
     for tool in tools {
-        let tool_idx = tool::add_or_get_tool(tool, &conn)?;
-        tool_to_scriptlet::link_scriptlet_to_tool(tool_idx, scriptlet_idx, &conn)?;
+        let tool_idx = tool::add_or_get_tool(tool, conn)?;
+        tool_to_scriptlet::link_scriptlet_to_tool(tool_idx, id, conn)?;
     }
     Ok(())
 }
 
-pub fn get_all_scriptlets() -> Result<Vec<ScriptletData>, DocuError> {
+fn link_tags(id: i64, tags: &[String], conn: &MutexGuard<Connection>) -> Result<(), DocuError> {
+    for tag in tags {
+        let tag_idx = tag::add_or_get_tag(tag, conn)?;
+        tag_to_scriptlet::link_scriptlet_to_tag(tag_idx, id, conn)?;
+    }
+    Ok(())
+}
+
+/// The id of the scriptlet using this name, if any. Scriptlets in the trash are included.
+pub fn find_scriptlet_by_name(name: &str) -> Result<Option<i64>, DocuError> {
+    let conn = get_read_conn()?;
+    scriptlet::get_id_by_name(name, &conn)
+}
+
+pub fn get_scriptlet_by_id(id: i64) -> Result<Option<ScriptletData>, DocuError> {
+    let conn = get_read_conn()?;
+    get_scriptlet(id, &conn)
+}
+
+pub fn get_scriptlet_revisions(id: i64) -> Result<Vec<RevisionData>, DocuError> {
+    let conn = get_read_conn()?;
+    revision::get_revisions(id, &conn)
+}
+
+pub fn get_scriptlets(filter: &ScriptletFilter) -> Result<Vec<ScriptletData>, DocuError> {
+    let conn = get_read_conn()?;
+    scriptlet::get_scriptlets(filter, &conn)
+}
+
+pub fn search_scriptlets(
+    query: &str,
+    sort: Option<SortOrder>,
+    created: &TimeRange,
+) -> Result<Vec<ScriptletData>, DocuError> {
+    let conn = get_read_conn()?;
+    match_scriptlets(query, sort, created, &conn)
+}
+
+pub fn record_usage(id: i64, kind: UsageKind) -> Result<(), DocuError> {
     let conn = get_conn()?;
-    scriptlet::get_scriptlets(&conn)
+    usage::insert_usage(id, kind, &conn)
 }
 
-pub fn get_scriptlets_for_tool(tool_name: &str) -> Result<Vec<ScriptletData>, DocuError> {
+/// Moves scriptlets to the trash, returning the ids that were actually moved.
+pub fn trash_scriptlets(ids: &[i64]) -> Result<Vec<i64>, DocuError> {
     let conn = get_conn()?;
-    let result = tool::get_tool_id(tool_name, &conn);
-    match result {
-        Ok(tool_idx) => tool_to_scriptlet::get_from_tool_id(tool_idx, &conn),
-        Err(DatabaseSql(Error::QueryReturnedNoRows)) => Ok(Vec::new()),
-        Err(err) => Err(err),
+    let mut trashed = Vec::new();
+    for &id in ids {
+        if scriptlet::trash_row(id, &conn)? {
+            trashed.push(id);
+        }
     }
+    Ok(trashed)
 }
-pub fn search_scriptlets(query: &str) -> Result<Vec<ScriptletData>, DocuError> {
+
+/// Takes scriptlets back out of the trash, returning the ids that were actually restored.
+pub fn restore_scriptlets(ids: &[i64]) -> Result<Vec<i64>, DocuError> {
     let conn = get_conn()?;
-    match_scriptlets(query, &conn)
+    let mut restored = Vec::new();
+    for &id in ids {
+        if scriptlet::restore_row(id, &conn)? {
+            restored.push(id);
+        }
+    }
+    Ok(restored)
 }
 
-pub fn remove_scriptlets(ids: Vec<i64>) -> Result<(), DocuError> {
+pub fn get_trashed_scriptlets() -> Result<Vec<TrashedScriptletData>, DocuError> {
+    let conn = get_read_conn()?;
+    scriptlet::get_trashed(&conn)
+}
+
+/// Permanently deletes every scriptlet that has been in the trash for at least `older_than`,
+/// along with everything linked to it. Returns how many scriptlets were deleted.
+pub fn empty_trash(older_than: Duration) -> Result<usize, DocuError> {
     let conn = get_conn()?;
-    for id in ids {
+    let tx = conn.unchecked_transaction()?;
+    let ids = scriptlet::get_trashed_ids_older_than(older_than.as_secs(), &conn)?;
+    for &id in &ids {
         remove_scriptlet(id, &conn)?;
+        revision::remove_revisions(id, &conn)?;
+        tag_to_scriptlet::unlink_scriptlet(id, &conn)?;
+        tool_to_scriptlet::unlink_scriptlet(id, &conn)?;
+        usage::remove_usage(id, &conn)?;
+        placeholder::remove_placeholders(id, &conn)?;
+    }
+    tx.commit()?;
+    Ok(ids.len())
+}
+
+pub fn tag_scriptlet(id: i64, tags: &[String]) -> Result<(), DocuError> {
+    let conn = get_conn()?;
+    let tx = conn.unchecked_transaction()?;
+    link_tags(id, tags, &conn)?;
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn untag_scriptlet(id: i64, tags: &[String]) -> Result<(), DocuError> {
+    let conn = get_conn()?;
+    let tx = conn.unchecked_transaction()?;
+    for tag in tags {
+        if let Some(tag_idx) = tag::get_tag_id(tag, &conn)? {
+            tag_to_scriptlet::unlink_scriptlet_from_tag(tag_idx, id, &conn)?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn get_tools() -> Result<Vec<ToolData>, DocuError> {
+    let conn = get_read_conn()?;
+    tool::get_tools(&conn)
+}
+
+/// Removes links to scriptlets that no longer exist, then every tool left without scriptlets.
+/// Returns the number of links removed and the names of the tools removed.
+pub fn prune_tools() -> Result<(usize, Vec<String>), DocuError> {
+    let conn = get_conn()?;
+    let tx = conn.unchecked_transaction()?;
+    let links = tool_to_scriptlet::remove_dangling_links(&conn)?;
+    let tools = tool::remove_orphaned_tools(&conn)?;
+    tx.commit()?;
+    Ok((links, tools))
+}
+
+/// Moves every scriptlet of tool `from` onto tool `into`, creating `into` if needed, and then
+/// deletes `from`. Returns the number of scriptlets moved.
+pub fn merge_tools(from: &str, into: &str) -> Result<usize, DocuError> {
+    let conn = get_conn()?;
+    let tx = conn.unchecked_transaction()?;
+    let from_idx =
+        tool::get_tool_id(from, &conn)?.ok_or_else(|| Tool(format!("no tool named '{from}'")))?;
+    let into_idx = tool::add_or_get_tool(into, &conn)?;
+    if from_idx == into_idx {
+        return Err(Tool(format!("cannot merge '{from}' into itself")));
+    }
+    let moved = tool_to_scriptlet::move_links(from_idx, into_idx, &conn)?;
+    tool::remove_tool(from_idx, &conn)?;
+    tx.commit()?;
+    Ok(moved)
+}
+
+pub fn rename_tool(from: &str, to: &str) -> Result<(), DocuError> {
+    let conn = get_conn()?;
+    let from_idx =
+        tool::get_tool_id(from, &conn)?.ok_or_else(|| Tool(format!("no tool named '{from}'")))?;
+    if tool::get_tool_id(to, &conn)?.is_some() {
+        return Err(Tool(format!(
+            "a tool named '{to}' already exists, use `docu tools merge {from} {to}` instead"
+        )));
     }
+    tool::rename_tool(from_idx, to, &conn)
+}
+
+/// Checks the database file, the full text index and the tool links without changing anything.
+pub fn check_database() -> Result<DoctorReport, DocuError> {
+    let conn = get_read_conn()?;
+    Ok(DoctorReport {
+        integrity_errors: doctor::integrity_errors(&conn)?,
+        unindexed: doctor::unindexed_ids(&conn)?,
+        stale_index: doctor::stale_index_ids(&conn)?,
+        dangling_links: tool_to_scriptlet::count_dangling_links(&conn)?,
+        orphaned_tools: tool::get_orphaned_tools(&conn)?,
+    })
+}
+
+/// Rebuilds the full text index and removes dangling tool links and unused tools.
+pub fn repair_database() -> Result<(), DocuError> {
+    let conn = get_conn()?;
+    let tx = conn.unchecked_transaction()?;
+    doctor::rebuild_index(&conn)?;
+    tool_to_scriptlet::remove_dangling_links(&conn)?;
+    tool::remove_orphaned_tools(&conn)?;
+    tx.commit()?;
     Ok(())
 }
+
+pub fn backup_database(path: &Path) -> Result<(), DocuError> {
+    let conn = get_conn()?;
+    backup::backup_to(&conn, path)
+}
+
+/// Replaces the database with the backup at `path`, after checking it can be read and saving a
+/// copy of the current database next to it. Returns the schema version of the backup, which is
+/// then migrated to the latest version.
+pub fn restore_database(path: &Path) -> Result<i64, DocuError> {
+    let version = backup::check_restorable(path)?;
+    let mut conn = get_conn()?;
+    let mut safety_copy = database_path().into_os_string();
+    safety_copy.push(".pre-restore.bak");
+    let safety_copy = PathBuf::from(safety_copy);
+    if safety_copy.exists() {
+        fs::remove_file(&safety_copy)?;
+    }
+    backup::backup_to(&conn, &safety_copy)?;
+    backup::restore_from(&mut conn, path)?;
+    migrate::migrate(&mut conn, &database_path())?;
+    Ok(version)
+}
+
+pub fn migration_status() -> Result<MigrationStatus, DocuError> {
+    let conn = open_database()?;
+    migrate::status(&conn)
+}
+
+pub fn run_migrations() -> Result<Vec<&'static Migration>, DocuError> {
+    let mut conn = open_database()?;
+    migrate::migrate(&mut conn, &database_path())
+}
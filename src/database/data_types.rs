@@ -1,12 +1,295 @@
-#[derive(Clone, PartialEq)]
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ToolData {
-    pub(crate) name: String,
+    pub name: String,
+    pub scriptlet_count: i64,
+    pub last_used: Option<String>,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ScriptletData {
-    pub(crate) id: i64,
-    pub(crate) name: String,
-    pub(crate) command: String,
-    pub(crate) description: String,
+    pub id: i64,
+    pub name: String,
+    pub command: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    /// When the scriptlet was added, as a UTC `YYYY-MM-DD HH:MM:SS` timestamp.
+    pub created_at: String,
+    /// When the scriptlet was last changed, in the same format as `created_at`.
+    pub updated_at: String,
+    /// The page the scriptlet was copied from, if it was given one.
+    pub source_url: Option<String>,
+    /// The directory the scriptlet was saved from.
+    pub cwd: Option<String>,
+    /// The host the scriptlet was saved on.
+    pub host: Option<String>,
+    /// The placeholders in the command, in the order they first appear.
+    pub placeholders: Vec<PlaceholderData>,
+}
+
+/// A placeholder in the command of a scriptlet, such as `$FILE`, and what it stands for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaceholderData {
+    /// The name of the variable, without the `$`.
+    pub name: String,
+    pub kind: PlaceholderType,
+    /// Help text explaining what the value is for.
+    pub description: Option<String>,
+    /// The value to use when none is given.
+    pub default: Option<String>,
+    /// The values an `Enum` placeholder can take.
+    pub choices: Vec<String>,
+}
+
+impl PlaceholderData {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether a command still uses this placeholder, as `$NAME` or `${NAME}`.
+    pub fn used_in(&self, command: &str) -> bool {
+        command
+            .match_indices(&format!("${}", self.name))
+            .any(|(i, variable)| {
+                !command[i + variable.len()..]
+                    .starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+            })
+            || command.contains(&format!("${{{}}}", self.name))
+    }
+
+    /// Checks that a value fits the type of the placeholder, explaining why if it doesn't.
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match self.kind {
+            PlaceholderType::Int if value.trim().parse::<i64>().is_err() => {
+                Err("must be a whole number".to_string())
+            }
+            PlaceholderType::Enum if !self.choices.iter().any(|choice| choice == value) => {
+                Err(format!("must be one of {}", self.choices.join(", ")))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The kind of value a placeholder takes.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PlaceholderType {
+    Path,
+    Int,
+    /// One of a fixed list of choices.
+    Enum,
+    /// Any text.
+    #[default]
+    Text,
+}
+
+impl PlaceholderType {
+    pub const ALL: [PlaceholderType; 4] = [
+        PlaceholderType::Path,
+        PlaceholderType::Int,
+        PlaceholderType::Enum,
+        PlaceholderType::Text,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlaceholderType::Path => "path",
+            PlaceholderType::Int => "int",
+            PlaceholderType::Enum => "enum",
+            PlaceholderType::Text => "text",
+        }
+    }
+
+    /// The type named by `as_str`, falling back to `Text` for names that aren't known.
+    pub fn from_name(name: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == name)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashedScriptletData {
+    pub scriptlet: ScriptletData,
+    pub deleted_at: String,
+}
+
+/// A scriptlet to add to a store, or to replace an existing one with. Only the name and command
+/// are required, e.g. `NewScriptlet::new("ports", "ss -tlnp").description("Listening ports")`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NewScriptlet {
+    pub name: String,
+    pub command: String,
+    pub description: String,
+    pub tools: Vec<String>,
+    pub tags: Vec<String>,
+    pub source_url: Option<String>,
+    pub cwd: Option<String>,
+    pub host: Option<String>,
+    /// When the scriptlet was first used, if that was before it was added, e.g. when importing it
+    /// from a history. Only used when adding a scriptlet, never when replacing one.
+    pub created_at: Option<SystemTime>,
+    pub placeholders: Vec<PlaceholderData>,
+}
+
+impl NewScriptlet {
+    pub fn new(name: impl Into<String>, command: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            command: command.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn tools<S: Into<String>>(mut self, tools: impl IntoIterator<Item = S>) -> Self {
+        self.tools = tools.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn tags<S: Into<String>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn source_url(mut self, source_url: impl Into<String>) -> Self {
+        self.source_url = Some(source_url.into());
+        self
+    }
+
+    pub fn cwd(mut self, cwd: impl Into<String>) -> Self {
+        self.cwd = Some(cwd.into());
+        self
+    }
+
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    pub fn created_at(mut self, created_at: SystemTime) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    pub fn placeholders(mut self, placeholders: impl IntoIterator<Item = PlaceholderData>) -> Self {
+        self.placeholders = placeholders.into_iter().collect();
+        self
+    }
+}
+
+/// Narrows down which scriptlets are listed. Every filter that is set must match.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptletFilter {
+    pub tool: Option<String>,
+    pub tag: Option<String>,
+    pub created: TimeRange,
+    pub sort: SortOrder,
+}
+
+impl ScriptletFilter {
+    pub fn tool(mut self, tool: impl Into<String>) -> Self {
+        self.tool = Some(tool.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    pub fn created(mut self, created: TimeRange) -> Self {
+        self.created = created;
+        self
+    }
+
+    pub fn sort(mut self, sort: SortOrder) -> Self {
+        self.sort = sort;
+        self
+    }
+}
+
+/// A span of time that is open ended unless `since` or `before` is set. `since` is inclusive and
+/// `before` is exclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimeRange {
+    pub since: Option<SystemTime>,
+    pub before: Option<SystemTime>,
+}
+
+impl TimeRange {
+    pub fn contains(&self, time: SystemTime) -> bool {
+        self.since.is_none_or(|since| time >= since)
+            && self.before.is_none_or(|before| time < before)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SortOrder {
+    /// Most recently added first.
+    #[default]
+    Recent,
+    /// Most frequently and recently used first.
+    Frecency,
+}
+
+/// The ways a scriptlet can be used, recorded to rank scriptlets by frecency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UsageKind {
+    Select,
+    Copy,
+    Run,
+}
+
+impl UsageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsageKind::Select => "select",
+            UsageKind::Copy => "copy",
+            UsageKind::Run => "run",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevisionData {
+    pub revision: i64,
+    pub name: String,
+    pub command: String,
+    pub description: String,
+    pub time: String,
+}
+
+/// The problems found by `docu doctor`. Only the full text index and the tool links can be
+/// repaired; integrity errors mean the database file itself is damaged.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DoctorReport {
+    pub integrity_errors: Vec<String>,
+    pub unindexed: Vec<i64>,
+    pub stale_index: Vec<i64>,
+    pub dangling_links: usize,
+    pub orphaned_tools: Vec<String>,
+}
+
+impl DoctorReport {
+    pub fn index_out_of_sync(&self) -> bool {
+        !self.unindexed.is_empty() || !self.stale_index.is_empty()
+    }
+
+    pub fn links_broken(&self) -> bool {
+        self.dangling_links > 0 || !self.orphaned_tools.is_empty()
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.integrity_errors.is_empty() && !self.index_out_of_sync() && !self.links_broken()
+    }
 }
@@ -0,0 +1,54 @@
+use crate::errors::error::DocuError;
+use rusqlite::Connection;
+use std::sync::MutexGuard;
+
+/// The problems reported by SQLite's own integrity check, or nothing if the file is sound.
+pub(crate) fn integrity_errors(conn: &MutexGuard<Connection>) -> Result<Vec<String>, DocuError> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let errors: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()
+        .map_err(DocuError::from)?;
+    Ok(errors.into_iter().filter(|e| e != "ok").collect())
+}
+
+/// Ids of scriptlets outside the trash that are missing from the full text index.
+pub(crate) fn unindexed_ids(conn: &MutexGuard<Connection>) -> Result<Vec<i64>, DocuError> {
+    query_ids(
+        "SELECT id FROM scriptlet
+         WHERE deleted_at IS NULL AND id NOT IN (SELECT id FROM scriptlet_fts_docsize)
+         ORDER BY id",
+        conn,
+    )
+}
+
+/// Ids in the full text index that belong to trashed or deleted scriptlets. The index is read
+/// through its docsize shadow table, as reading `scriptlet_fts` itself returns the content table.
+pub(crate) fn stale_index_ids(conn: &MutexGuard<Connection>) -> Result<Vec<i64>, DocuError> {
+    query_ids(
+        "SELECT id FROM scriptlet_fts_docsize
+         WHERE id NOT IN (SELECT id FROM scriptlet WHERE deleted_at IS NULL)
+         ORDER BY id",
+        conn,
+    )
+}
+
+/// Throws away the full text index and indexes every scriptlet outside the trash again. FTS5's
+/// own `rebuild` can't be used, as it would also index the trash.
+pub(crate) fn rebuild_index(conn: &MutexGuard<Connection>) -> Result<(), DocuError> {
+    conn.execute_batch(
+        "INSERT INTO scriptlet_fts(scriptlet_fts) VALUES('delete-all');
+         INSERT INTO scriptlet_fts(rowid, name, description, command, tags)
+           SELECT id, name, description, command, tags FROM scriptlet WHERE deleted_at IS NULL;",
+    )?;
+    Ok(())
+}
+
+fn query_ids(sql: &str, conn: &MutexGuard<Connection>) -> Result<Vec<i64>, DocuError> {
+    let mut stmt = conn.prepare(sql)?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()
+        .map_err(DocuError::from)?;
+    Ok(ids)
+}
@@ -0,0 +1,57 @@
+use crate::config::load::CONFIG;
+use crate::errors::error::DocuError;
+use crate::errors::error::DocuError::Config;
+use dirs::{data_dir, home_dir};
+use std::env;
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const DATABASE_ENV_VAR: &str = "DOCU_DB";
+
+static DATABASE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Chooses the database every later command will use. An explicit `--db` path wins, followed by a
+/// named `--library` from the config, then the `DOCU_DB` environment variable, and finally the
+/// default database in the data directory.
+pub fn select_database(db: Option<PathBuf>, library: Option<&str>) -> Result<(), DocuError> {
+    let path = match (db, library) {
+        (Some(path), _) => path,
+        (None, Some(library)) => CONFIG
+            .libraries
+            .get(library)
+            .map(|path| expand_home(path))
+            .ok_or_else(|| Config(format!("no library named '{library}' in config.toml")))?,
+        (None, None) => match env::var_os(DATABASE_ENV_VAR) {
+            Some(path) if !path.is_empty() => expand_home(&PathBuf::from(path)),
+            _ => default_database_path(),
+        },
+    };
+    DATABASE_PATH
+        .set(path)
+        .map_err(|_| Config("database location has already been chosen".to_string()))
+}
+
+pub fn database_path() -> PathBuf {
+    let path = DATABASE_PATH
+        .get_or_init(default_database_path)
+        .to_path_buf();
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        create_dir_all(parent).expect("Failed to create database directory");
+    }
+    path
+}
+
+fn default_database_path() -> PathBuf {
+    let mut path = data_dir().expect("Failed to obtain data directory");
+    path.push("docu");
+    path.push("docu.db");
+    path
+}
+
+pub(crate) fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
@@ -0,0 +1,137 @@
+use crate::errors::error::DocuError;
+use crate::errors::error::DocuError::Migration as MigrationError;
+use rusqlite::Connection;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    sql: &'static str,
+}
+
+/// Every schema change ever made to the database, in the order it must be applied. Existing
+/// entries must never be edited once released - add a new migration instead.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create scriptlet, tool and full text search tables",
+        sql: include_str!("migrations/001_initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "Keep previous versions of scriptlets as revisions",
+        sql: include_str!("migrations/002_scriptlet_revision.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "Add tags and include them in full text search",
+        sql: include_str!("migrations/003_tags.sql"),
+    },
+    Migration {
+        version: 4,
+        description: "Record scriptlet usage for frecency ordering",
+        sql: include_str!("migrations/004_usage.sql"),
+    },
+    Migration {
+        version: 5,
+        description: "Move removed scriptlets to a trash instead of deleting them",
+        sql: include_str!("migrations/005_trash.sql"),
+    },
+    Migration {
+        version: 6,
+        description: "Record when scriptlets were updated and where they came from",
+        sql: include_str!("migrations/006_metadata.sql"),
+    },
+    Migration {
+        version: 7,
+        description: "Describe the placeholders in scriptlets with their types and defaults",
+        sql: include_str!("migrations/007_placeholders.sql"),
+    },
+];
+
+pub struct MigrationStatus {
+    pub current_version: i64,
+    pub applied: Vec<&'static Migration>,
+    pub pending: Vec<&'static Migration>,
+}
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+pub fn schema_version(conn: &Connection) -> Result<i64, DocuError> {
+    let version = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    Ok(version)
+}
+
+pub fn status(conn: &Connection) -> Result<MigrationStatus, DocuError> {
+    let current_version = schema_version(conn)?;
+    let (applied, pending) = MIGRATIONS
+        .iter()
+        .partition(|m| m.version <= current_version);
+    Ok(MigrationStatus {
+        current_version,
+        applied,
+        pending,
+    })
+}
+
+/// Brings the database up to the latest schema version, returning the migrations that were
+/// applied. Each migration runs in its own transaction together with the version bump, so a
+/// failure leaves the database at the last successfully applied version. If the database already
+/// holds data it is backed up next to `database_path` before anything is changed.
+pub fn migrate(
+    conn: &mut Connection,
+    database_path: &Path,
+) -> Result<Vec<&'static Migration>, DocuError> {
+    let MigrationStatus {
+        current_version,
+        pending,
+        ..
+    } = status(conn)?;
+    if current_version > latest_version() {
+        return Err(MigrationError(format!(
+            "database schema version {current_version} is newer than the latest version supported by this docu ({})",
+            latest_version()
+        )));
+    }
+    if pending.is_empty() {
+        return Ok(pending);
+    }
+    if has_existing_schema(conn)? {
+        backup_before_migration(conn, database_path, current_version)?;
+    }
+    for migration in &pending {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql).map_err(|e| {
+            MigrationError(format!(
+                "migration {} ({}) failed: {e}",
+                migration.version, migration.description
+            ))
+        })?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+    }
+    Ok(pending)
+}
+
+fn has_existing_schema(conn: &Connection) -> Result<bool, DocuError> {
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get(0))?;
+    Ok(count > 0)
+}
+
+fn backup_before_migration(
+    conn: &Connection,
+    database_path: &Path,
+    version: i64,
+) -> Result<(), DocuError> {
+    let mut backup_path = database_path.as_os_str().to_owned();
+    backup_path.push(format!(".v{version}.bak"));
+    let backup_path = PathBuf::from(backup_path);
+    if backup_path.exists() {
+        fs::remove_file(&backup_path)?;
+    }
+    conn.execute("VACUUM INTO ?1", [backup_path.to_string_lossy()])?;
+    Ok(())
+}
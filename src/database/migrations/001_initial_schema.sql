@@ -0,0 +1,43 @@
+CREATE TABLE IF NOT EXISTS scriptlet (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    name        TEXT NOT NULL UNIQUE,
+    command     TEXT NOT NULL,
+    description TEXT,
+    time        DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE IF NOT EXISTS tool (
+    id   INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    time DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE IF NOT EXISTS tool_scriptlet (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    tool_id       INTEGER NOT NULL,
+    scriptlet_id  INTEGER NOT NULL,
+    UNIQUE(tool_id, scriptlet_id),
+    FOREIGN KEY(tool_id)      REFERENCES tool(id) ON DELETE CASCADE,
+    FOREIGN KEY(scriptlet_id) REFERENCES scriptlet(id) ON DELETE CASCADE
+);
+CREATE VIRTUAL TABLE IF NOT EXISTS scriptlet_fts
+USING fts5(
+    name,
+    description,
+    command,
+    tokenize='porter',
+    content='scriptlet',
+    content_rowid='id'
+);
+CREATE TRIGGER IF NOT EXISTS scriptlet_ai AFTER INSERT ON scriptlet BEGIN
+  INSERT INTO scriptlet_fts(rowid, name, description, command)
+    VALUES (new.id, new.name, new.description, new.command);
+END;
+CREATE TRIGGER IF NOT EXISTS scriptlet_ad AFTER DELETE ON scriptlet BEGIN
+  INSERT INTO scriptlet_fts(scriptlet_fts, rowid, name, description, command)
+    VALUES('delete', old.id, old.name, old.description, old.command);
+END;
+CREATE TRIGGER IF NOT EXISTS scriptlet_au AFTER UPDATE ON scriptlet BEGIN
+  INSERT INTO scriptlet_fts(scriptlet_fts, rowid, name, description, command)
+    VALUES('delete', old.id, old.name, old.description, old.command);
+  INSERT INTO scriptlet_fts(rowid, name, description, command)
+    VALUES (new.id, new.name, new.description, new.command);
+END;
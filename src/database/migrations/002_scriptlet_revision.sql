@@ -0,0 +1,11 @@
+CREATE TABLE scriptlet_revision (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    scriptlet_id INTEGER NOT NULL,
+    revision     INTEGER NOT NULL,
+    name         TEXT NOT NULL,
+    command      TEXT NOT NULL,
+    description  TEXT,
+    time         DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(scriptlet_id, revision),
+    FOREIGN KEY(scriptlet_id) REFERENCES scriptlet(id) ON DELETE CASCADE
+);
@@ -0,0 +1,64 @@
+CREATE TABLE tag (
+    id   INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    time DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE tag_scriptlet (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    tag_id        INTEGER NOT NULL,
+    scriptlet_id  INTEGER NOT NULL,
+    UNIQUE(tag_id, scriptlet_id),
+    FOREIGN KEY(tag_id)       REFERENCES tag(id) ON DELETE CASCADE,
+    FOREIGN KEY(scriptlet_id) REFERENCES scriptlet(id) ON DELETE CASCADE
+);
+
+-- Tags are mirrored onto the scriptlet as a space separated column so they can be part of the
+-- external content full text index.
+ALTER TABLE scriptlet ADD COLUMN tags TEXT NOT NULL DEFAULT '';
+
+DROP TRIGGER scriptlet_ai;
+DROP TRIGGER scriptlet_ad;
+DROP TRIGGER scriptlet_au;
+DROP TABLE scriptlet_fts;
+CREATE VIRTUAL TABLE scriptlet_fts
+USING fts5(
+    name,
+    description,
+    command,
+    tags,
+    tokenize='porter',
+    content='scriptlet',
+    content_rowid='id'
+);
+INSERT INTO scriptlet_fts(scriptlet_fts) VALUES('rebuild');
+CREATE TRIGGER scriptlet_ai AFTER INSERT ON scriptlet BEGIN
+  INSERT INTO scriptlet_fts(rowid, name, description, command, tags)
+    VALUES (new.id, new.name, new.description, new.command, new.tags);
+END;
+CREATE TRIGGER scriptlet_ad AFTER DELETE ON scriptlet BEGIN
+  INSERT INTO scriptlet_fts(scriptlet_fts, rowid, name, description, command, tags)
+    VALUES('delete', old.id, old.name, old.description, old.command, old.tags);
+END;
+CREATE TRIGGER scriptlet_au AFTER UPDATE ON scriptlet BEGIN
+  INSERT INTO scriptlet_fts(scriptlet_fts, rowid, name, description, command, tags)
+    VALUES('delete', old.id, old.name, old.description, old.command, old.tags);
+  INSERT INTO scriptlet_fts(rowid, name, description, command, tags)
+    VALUES (new.id, new.name, new.description, new.command, new.tags);
+END;
+
+CREATE TRIGGER tag_scriptlet_ai AFTER INSERT ON tag_scriptlet BEGIN
+  UPDATE scriptlet SET tags = (
+    SELECT COALESCE(group_concat(name, ' '), '') FROM (
+      SELECT t.name FROM tag t JOIN tag_scriptlet ts ON t.id = ts.tag_id
+      WHERE ts.scriptlet_id = new.scriptlet_id ORDER BY t.name
+    )
+  ) WHERE id = new.scriptlet_id;
+END;
+CREATE TRIGGER tag_scriptlet_ad AFTER DELETE ON tag_scriptlet BEGIN
+  UPDATE scriptlet SET tags = (
+    SELECT COALESCE(group_concat(name, ' '), '') FROM (
+      SELECT t.name FROM tag t JOIN tag_scriptlet ts ON t.id = ts.tag_id
+      WHERE ts.scriptlet_id = old.scriptlet_id ORDER BY t.name
+    )
+  ) WHERE id = old.scriptlet_id;
+END;
@@ -0,0 +1,22 @@
+CREATE TABLE usage (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    scriptlet_id  INTEGER NOT NULL,
+    kind          TEXT NOT NULL,
+    time          DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(scriptlet_id) REFERENCES scriptlet(id) ON DELETE CASCADE
+);
+CREATE INDEX usage_scriptlet_id ON usage(scriptlet_id);
+
+-- Frecency weights every use by how recently it happened, so a scriptlet used a lot last year
+-- ranks below one used a few times this week.
+CREATE VIEW scriptlet_frecency AS
+SELECT scriptlet_id,
+       SUM(CASE
+             WHEN julianday('now') - julianday(time) < 4  THEN 100
+             WHEN julianday('now') - julianday(time) < 14 THEN 70
+             WHEN julianday('now') - julianday(time) < 31 THEN 50
+             WHEN julianday('now') - julianday(time) < 90 THEN 30
+             ELSE 10
+           END) AS score
+FROM usage
+GROUP BY scriptlet_id;
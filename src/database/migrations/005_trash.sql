@@ -0,0 +1,23 @@
+-- Removed scriptlets are moved to the trash by setting deleted_at, and only deleted for good
+-- when the trash is emptied. Trashed scriptlets are kept out of the full text index.
+ALTER TABLE scriptlet ADD COLUMN deleted_at DATETIME;
+
+DROP TRIGGER scriptlet_ai;
+DROP TRIGGER scriptlet_ad;
+DROP TRIGGER scriptlet_au;
+CREATE TRIGGER scriptlet_ai AFTER INSERT ON scriptlet WHEN new.deleted_at IS NULL BEGIN
+  INSERT INTO scriptlet_fts(rowid, name, description, command, tags)
+    VALUES (new.id, new.name, new.description, new.command, new.tags);
+END;
+CREATE TRIGGER scriptlet_ad AFTER DELETE ON scriptlet WHEN old.deleted_at IS NULL BEGIN
+  INSERT INTO scriptlet_fts(scriptlet_fts, rowid, name, description, command, tags)
+    VALUES('delete', old.id, old.name, old.description, old.command, old.tags);
+END;
+CREATE TRIGGER scriptlet_au AFTER UPDATE ON scriptlet BEGIN
+  INSERT INTO scriptlet_fts(scriptlet_fts, rowid, name, description, command, tags)
+    SELECT 'delete', old.id, old.name, old.description, old.command, old.tags
+    WHERE old.deleted_at IS NULL;
+  INSERT INTO scriptlet_fts(rowid, name, description, command, tags)
+    SELECT new.id, new.name, new.description, new.command, new.tags
+    WHERE new.deleted_at IS NULL;
+END;
@@ -0,0 +1,7 @@
+-- When a scriptlet was last changed, and where it came from: the page it was copied from and the
+-- directory and host it was saved on.
+ALTER TABLE scriptlet ADD COLUMN updated_at DATETIME;
+ALTER TABLE scriptlet ADD COLUMN source_url TEXT;
+ALTER TABLE scriptlet ADD COLUMN cwd TEXT;
+ALTER TABLE scriptlet ADD COLUMN host TEXT;
+UPDATE scriptlet SET updated_at = time;
@@ -0,0 +1,15 @@
+-- The placeholders in a scriptlet's command, such as `$FILE`, with what each one stands for, the
+-- type of value it takes and the value to use when none is given. Placeholders that take one of
+-- a fixed list store their choices one per line.
+CREATE TABLE placeholder (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    scriptlet_id  INTEGER NOT NULL,
+    position      INTEGER NOT NULL,
+    name          TEXT NOT NULL,
+    description   TEXT,
+    type          TEXT NOT NULL DEFAULT 'text',
+    choices       TEXT NOT NULL DEFAULT '',
+    default_value TEXT,
+    UNIQUE(scriptlet_id, name),
+    FOREIGN KEY(scriptlet_id) REFERENCES scriptlet(id) ON DELETE CASCADE
+);
@@ -1,5 +1,15 @@
-pub mod connect;
+mod backup;
+pub(crate) mod connect;
 pub mod data_types;
+mod doctor;
+pub mod location;
+pub mod migrate;
+mod placeholder;
+mod revision;
 mod scriptlet;
+mod tag;
+mod tag_to_scriptlet;
+pub mod timestamp;
 mod tool;
 mod tool_to_scriptlet;
+mod usage;
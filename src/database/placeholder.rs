@@ -0,0 +1,88 @@
+use crate::database::data_types::{PlaceholderData, PlaceholderType};
+use crate::errors::error::DocuError;
+use rusqlite::{Connection, params};
+use std::sync::MutexGuard;
+
+pub(crate) fn insert_placeholders(
+    scriptlet_id: i64,
+    placeholders: &[PlaceholderData],
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    for (position, placeholder) in placeholders.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO placeholder
+                 (scriptlet_id, position, name, type, description, default_value, choices)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                scriptlet_id,
+                position as i64,
+                placeholder.name,
+                placeholder.kind.as_str(),
+                placeholder.description,
+                placeholder.default,
+                placeholder.choices.join("\n")
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn get_placeholders(
+    scriptlet_id: i64,
+    conn: &MutexGuard<Connection>,
+) -> Result<Vec<PlaceholderData>, DocuError> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT name, type, description, default_value, choices
+         FROM placeholder
+         WHERE scriptlet_id = ?1
+         ORDER BY position",
+    )?;
+    let placeholders = stmt
+        .query_map([scriptlet_id], |row| {
+            Ok(PlaceholderData {
+                name: row.get(0)?,
+                kind: PlaceholderType::from_name(&row.get::<_, String>(1)?),
+                description: row.get(2)?,
+                default: row.get(3)?,
+                choices: row
+                    .get::<_, String>(4)?
+                    .lines()
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })?
+        .collect::<Result<_, _>>()
+        .map_err(DocuError::from)?;
+    Ok(placeholders)
+}
+
+/// Replaces the placeholders of a scriptlet with `placeholders`, or if none are given, removes
+/// the ones `command` no longer uses.
+pub(crate) fn replace_placeholders(
+    scriptlet_id: i64,
+    command: &str,
+    placeholders: &[PlaceholderData],
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    let kept: Vec<PlaceholderData> = if placeholders.is_empty() {
+        get_placeholders(scriptlet_id, conn)?
+            .into_iter()
+            .filter(|placeholder| placeholder.used_in(command))
+            .collect()
+    } else {
+        placeholders.to_vec()
+    };
+    remove_placeholders(scriptlet_id, conn)?;
+    insert_placeholders(scriptlet_id, &kept, conn)
+}
+
+pub(crate) fn remove_placeholders(
+    scriptlet_id: i64,
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    conn.execute(
+        "DELETE FROM placeholder WHERE scriptlet_id = ?1",
+        [scriptlet_id],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::database::data_types::{RevisionData, ScriptletData};
+use crate::errors::error::DocuError;
+use rusqlite::{Connection, Error, Row, params};
+use std::sync::MutexGuard;
+
+/// Stores the given version of a scriptlet as its next revision, before it is overwritten.
+pub(crate) fn insert_revision(
+    scriptlet: &ScriptletData,
+    conn: &MutexGuard<Connection>,
+) -> Result<i64, DocuError> {
+    conn.execute(
+        "INSERT INTO scriptlet_revision (scriptlet_id, revision, name, command, description)
+         SELECT ?1, COALESCE(MAX(revision), 0) + 1, ?2, ?3, ?4
+         FROM scriptlet_revision WHERE scriptlet_id = ?1",
+        params![
+            scriptlet.id,
+            scriptlet.name,
+            scriptlet.command,
+            scriptlet.description
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub(crate) fn get_revisions(
+    scriptlet_id: i64,
+    conn: &MutexGuard<Connection>,
+) -> Result<Vec<RevisionData>, DocuError> {
+    let mut stmt = conn.prepare(
+        "SELECT revision, name, command, description, time
+         FROM scriptlet_revision
+         WHERE scriptlet_id = ?1
+         ORDER BY revision",
+    )?;
+    let revisions = stmt
+        .query_map([scriptlet_id], convert_to_revision_data)?
+        .collect::<Result<_, _>>()
+        .map_err(DocuError::from)?;
+    Ok(revisions)
+}
+
+pub(crate) fn remove_revisions(
+    scriptlet_id: i64,
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    conn.execute(
+        "DELETE FROM scriptlet_revision WHERE scriptlet_id = ?1",
+        [scriptlet_id],
+    )?;
+    Ok(())
+}
+
+fn convert_to_revision_data(row: &Row) -> Result<RevisionData, Error> {
+    Ok(RevisionData {
+        revision: row.get(0)?,
+        name: row.get(1)?,
+        command: row.get(2)?,
+        description: row.get(3)?,
+        time: row.get(4)?,
+    })
+}
@@ -1,35 +1,142 @@
-use crate::database::data_types::ScriptletData;
+use crate::database::data_types::{
+    NewScriptlet, ScriptletData, ScriptletFilter, SortOrder, TimeRange, TrashedScriptletData,
+};
+use crate::database::placeholder::get_placeholders;
+use crate::database::timestamp::{format_timestamp, unix_seconds};
 use crate::errors::error::DocuError;
-use crate::errors::error::DocuError::DatabaseSql;
-use rusqlite::{Connection, Error, Row};
+use rusqlite::{Connection, Error, OptionalExtension, Row, params};
 use std::sync::MutexGuard;
 
+/// The columns `convert_to_scriptlet_data` reads, from the scriptlet table aliased as `s`.
+const SCRIPTLET_COLUMNS: &str = "s.id, s.name, s.command, s.description, s.tags, s.time,
+    COALESCE(s.updated_at, s.time), s.source_url, s.cwd, s.host";
+
 pub(crate) fn insert_row(
-    title: &str,
-    command: &str,
-    description: &str,
+    scriptlet: &NewScriptlet,
     conn: &MutexGuard<Connection>,
 ) -> Result<i64, DocuError> {
     conn.execute(
-        "INSERT INTO scriptlet (name, command, description) VALUES (?, ?, ?)",
-        [title, command, description],
-    )
-    .map_err(DatabaseSql)?;
+        "INSERT INTO scriptlet (name, command, description, time, updated_at, source_url, cwd, host)
+         VALUES (?1, ?2, ?3, COALESCE(?7, CURRENT_TIMESTAMP), COALESCE(?7, CURRENT_TIMESTAMP),
+                 ?4, ?5, ?6)",
+        params![
+            scriptlet.name,
+            scriptlet.command,
+            scriptlet.description,
+            scriptlet.source_url,
+            scriptlet.cwd,
+            scriptlet.host,
+            scriptlet.created_at.map(format_timestamp)
+        ],
+    )?;
     Ok(conn.last_insert_rowid())
 }
 
-pub fn get_scriptlets(conn: &MutexGuard<Connection>) -> Result<Vec<ScriptletData>, DocuError> {
-    let mut stmt =
-        conn.prepare("SELECT id, name, command, description FROM scriptlet ORDER BY time DESC")?;
+/// Replaces the text of a scriptlet. Where it came from is only replaced if the new scriptlet
+/// says, so editing a scriptlet keeps its source, directory and host.
+pub(crate) fn update_row(
+    id: i64,
+    scriptlet: &NewScriptlet,
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    conn.execute(
+        "UPDATE scriptlet
+         SET name = ?2, command = ?3, description = ?4, updated_at = CURRENT_TIMESTAMP,
+             source_url = COALESCE(?5, source_url), cwd = COALESCE(?6, cwd),
+             host = COALESCE(?7, host)
+         WHERE id = ?1",
+        params![
+            id,
+            scriptlet.name,
+            scriptlet.command,
+            scriptlet.description,
+            scriptlet.source_url,
+            scriptlet.cwd,
+            scriptlet.host
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get_scriptlet(
+    id: i64,
+    conn: &MutexGuard<Connection>,
+) -> Result<Option<ScriptletData>, DocuError> {
+    let scriptlet = conn
+        .query_row(
+            &format!(
+                "SELECT {SCRIPTLET_COLUMNS}
+                 FROM scriptlet s
+                 WHERE s.id = ?1 AND s.deleted_at IS NULL"
+            ),
+            [id],
+            convert_to_scriptlet_data,
+        )
+        .optional()?;
+    scriptlet
+        .map(|scriptlet| with_placeholders(scriptlet, conn))
+        .transpose()
+}
+
+/// Looks up a scriptlet by name, including scriptlets in the trash since their names are still
+/// taken.
+pub(crate) fn get_id_by_name(
+    name: &str,
+    conn: &MutexGuard<Connection>,
+) -> Result<Option<i64>, DocuError> {
+    let id = conn
+        .query_row("SELECT id FROM scriptlet WHERE name = ?1", [name], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    Ok(id)
+}
+
+pub fn get_scriptlets(
+    filter: &ScriptletFilter,
+    conn: &MutexGuard<Connection>,
+) -> Result<Vec<ScriptletData>, DocuError> {
+    let order = match filter.sort {
+        SortOrder::Recent => "s.time DESC",
+        SortOrder::Frecency => "COALESCE(f.score, 0) DESC, s.time DESC",
+    };
+    let (since, before) = time_range_bounds(&filter.created);
+    let sql = format!(
+        "SELECT {SCRIPTLET_COLUMNS}
+         FROM   scriptlet s
+         LEFT   JOIN scriptlet_frecency f ON f.scriptlet_id = s.id
+         WHERE  s.deleted_at IS NULL
+           AND  (?1 IS NULL OR EXISTS (
+                    SELECT 1 FROM tool_scriptlet ts JOIN tool t ON t.id = ts.tool_id
+                    WHERE ts.scriptlet_id = s.id AND t.name = ?1))
+           AND  (?2 IS NULL OR EXISTS (
+                    SELECT 1 FROM tag_scriptlet ts JOIN tag t ON t.id = ts.tag_id
+                    WHERE ts.scriptlet_id = s.id AND t.name = ?2))
+           AND  (?3 IS NULL OR unixepoch(s.time) >= ?3)
+           AND  (?4 IS NULL OR unixepoch(s.time) < ?4)
+         ORDER  BY {order}"
+    );
+    let mut stmt = conn.prepare(&sql)?;
     let scriptlets: Vec<ScriptletData> = stmt
-        .query_map([], convert_to_scriptlet_data)?
+        .query_map(
+            params![filter.tool, filter.tag, since, before],
+            convert_to_scriptlet_data,
+        )?
         .collect::<Result<_, _>>()
-        .map_err(DatabaseSql)?;
-    Ok(scriptlets)
+        .map_err(DocuError::from)?;
+    scriptlets
+        .into_iter()
+        .map(|scriptlet| with_placeholders(scriptlet, conn))
+        .collect()
 }
 
+/// Full text search over scriptlets added within `created`. Without a sort order results are
+/// ranked by bm25 relevance; frecency blends that relevance with how often and recently each
+/// scriptlet has been used.
 pub fn match_scriptlets(
     query: &str,
+    sort: Option<SortOrder>,
+    created: &TimeRange,
     conn: &MutexGuard<Connection>,
 ) -> Result<Vec<ScriptletData>, DocuError> {
     let query = query
@@ -38,32 +145,106 @@ pub fn match_scriptlets(
         .map(|tok| format!("{tok}*"))
         .collect::<Vec<_>>()
         .join(" OR ");
-    let sql = "
-        SELECT s.id, s.name, s.command, s.description
+    let order = match sort {
+        None => "bm25(scriptlet_fts)",
+        Some(SortOrder::Recent) => "s.time DESC",
+        // bm25 is negative, with more relevant matches further below zero.
+        Some(SortOrder::Frecency) => "bm25(scriptlet_fts) * (1.0 + COALESCE(f.score, 0) / 100.0)",
+    };
+    let sql = format!(
+        "
+        SELECT {SCRIPTLET_COLUMNS}
         FROM   scriptlet_fts
         JOIN   scriptlet     AS s ON s.id = scriptlet_fts.rowid
+        LEFT   JOIN scriptlet_frecency AS f ON f.scriptlet_id = s.id
         WHERE  scriptlet_fts MATCH ?1
+          AND  s.deleted_at IS NULL
           AND  bm25(scriptlet_fts)  < ?2
-        ORDER  BY bm25(scriptlet_fts);
-    ";
-    let mut stmt = conn.prepare(sql)?;
-    let rows = stmt
-        .query_map([query, 10f32.to_string()], convert_to_scriptlet_data)?
+          AND  (?3 IS NULL OR unixepoch(s.time) >= ?3)
+          AND  (?4 IS NULL OR unixepoch(s.time) < ?4)
+        ORDER  BY {order};
+    "
+    );
+    let (since, before) = time_range_bounds(created);
+    let mut stmt = conn.prepare(&sql)?;
+    let rows: Vec<ScriptletData> = stmt
+        .query_map(
+            params![query, 10f32.to_string(), since, before],
+            convert_to_scriptlet_data,
+        )?
         .collect::<Result<_, _>>()?;
-    Ok(rows)
+    rows.into_iter()
+        .map(|scriptlet| with_placeholders(scriptlet, conn))
+        .collect()
+}
+
+/// Moves a scriptlet to the trash, returning whether it was there to be moved.
+pub(crate) fn trash_row(id: i64, conn: &MutexGuard<Connection>) -> Result<bool, DocuError> {
+    let changed = conn.execute(
+        "UPDATE scriptlet SET deleted_at = CURRENT_TIMESTAMP WHERE id = ?1 AND deleted_at IS NULL",
+        [id],
+    )?;
+    Ok(changed > 0)
+}
+
+/// Takes a scriptlet back out of the trash, returning whether it was in the trash.
+pub(crate) fn restore_row(id: i64, conn: &MutexGuard<Connection>) -> Result<bool, DocuError> {
+    let changed = conn.execute(
+        "UPDATE scriptlet SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+        [id],
+    )?;
+    Ok(changed > 0)
+}
+
+pub(crate) fn get_trashed(
+    conn: &MutexGuard<Connection>,
+) -> Result<Vec<TrashedScriptletData>, DocuError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {SCRIPTLET_COLUMNS}, s.deleted_at
+         FROM scriptlet s
+         WHERE s.deleted_at IS NOT NULL
+         ORDER BY s.deleted_at DESC"
+    ))?;
+    let trashed: Vec<TrashedScriptletData> = stmt
+        .query_map([], |row| {
+            Ok(TrashedScriptletData {
+                scriptlet: convert_to_scriptlet_data(row)?,
+                deleted_at: row.get(10)?,
+            })
+        })?
+        .collect::<Result<_, _>>()
+        .map_err(DocuError::from)?;
+    trashed
+        .into_iter()
+        .map(|trashed| {
+            Ok(TrashedScriptletData {
+                scriptlet: with_placeholders(trashed.scriptlet, conn)?,
+                ..trashed
+            })
+        })
+        .collect()
+}
+
+/// Ids of the scriptlets that were moved to the trash at least `age_seconds` ago. An age reaching
+/// further back than SQLite's dates makes the cutoff null, which no scriptlet is older than.
+pub(crate) fn get_trashed_ids_older_than(
+    age_seconds: u64,
+    conn: &MutexGuard<Connection>,
+) -> Result<Vec<i64>, DocuError> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM scriptlet
+         WHERE deleted_at IS NOT NULL
+           AND deleted_at <= datetime('now', ?1)",
+    )?;
+    let ids = stmt
+        .query_map([format!("-{age_seconds} seconds")], |row| row.get(0))?
+        .collect::<Result<_, _>>()
+        .map_err(DocuError::from)?;
+    Ok(ids)
 }
 
 pub(crate) fn remove_scriptlet(id: i64, conn: &MutexGuard<Connection>) -> Result<(), DocuError> {
-    // delete the main record
     conn.execute("DELETE FROM scriptlet WHERE id = ?1", [id])?;
-    // if you maintain a separate FTS table without DELETE triggers,
-    // uncomment the next lines to keep it in sync:
-    //
-    // conn.execute(
-    //     "DELETE FROM scriptlet_fts WHERE rowid = ?1",
-    //     [id],
-    // )?;
-    //
     Ok(())
 }
 
@@ -73,5 +254,32 @@ pub(crate) fn convert_to_scriptlet_data(row: &Row) -> Result<ScriptletData, Erro
         name: row.get(1)?,
         command: row.get(2)?,
         description: row.get(3)?,
+        tags: row
+            .get::<_, String>(4)?
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+        source_url: row.get(7)?,
+        cwd: row.get(8)?,
+        host: row.get(9)?,
+        placeholders: Vec::new(),
     })
 }
+
+/// Fills in the placeholders of a scriptlet read by `convert_to_scriptlet_data`.
+fn with_placeholders(
+    mut scriptlet: ScriptletData,
+    conn: &MutexGuard<Connection>,
+) -> Result<ScriptletData, DocuError> {
+    scriptlet.placeholders = get_placeholders(scriptlet.id, conn)?;
+    Ok(scriptlet)
+}
+
+fn time_range_bounds(range: &TimeRange) -> (Option<i64>, Option<i64>) {
+    (
+        range.since.map(unix_seconds),
+        range.before.map(unix_seconds),
+    )
+}
@@ -0,0 +1,27 @@
+use crate::errors::error::DocuError;
+use rusqlite::{Connection, OptionalExtension};
+use std::sync::MutexGuard;
+
+pub fn add_or_get_tag(name: &str, conn: &MutexGuard<Connection>) -> Result<i64, DocuError> {
+    if let Some(existing_id) = get_tag_id(name, conn)? {
+        return Ok(existing_id);
+    }
+    insert_row(name, conn)
+}
+
+pub(crate) fn get_tag_id(
+    name: &str,
+    conn: &MutexGuard<Connection>,
+) -> Result<Option<i64>, DocuError> {
+    let id = conn
+        .query_row("SELECT id FROM tag WHERE name = ?", [name], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    Ok(id)
+}
+
+fn insert_row(name: &str, conn: &MutexGuard<Connection>) -> Result<i64, DocuError> {
+    conn.execute("INSERT INTO tag (name) VALUES (?)", [name])?;
+    Ok(conn.last_insert_rowid())
+}
@@ -0,0 +1,36 @@
+use crate::errors::error::DocuError;
+use rusqlite::Connection;
+use rusqlite::params;
+use std::sync::MutexGuard;
+
+pub fn link_scriptlet_to_tag(
+    tag_id: i64,
+    scriptlet_id: i64,
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    conn.execute(
+        "INSERT OR IGNORE INTO tag_scriptlet (tag_id, scriptlet_id) VALUES (?1, ?2)",
+        params![tag_id, scriptlet_id],
+    )?;
+    Ok(())
+}
+
+pub fn unlink_scriptlet_from_tag(
+    tag_id: i64,
+    scriptlet_id: i64,
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    conn.execute(
+        "DELETE FROM tag_scriptlet WHERE tag_id = ?1 AND scriptlet_id = ?2",
+        params![tag_id, scriptlet_id],
+    )?;
+    Ok(())
+}
+
+pub fn unlink_scriptlet(scriptlet_id: i64, conn: &MutexGuard<Connection>) -> Result<(), DocuError> {
+    conn.execute(
+        "DELETE FROM tag_scriptlet WHERE scriptlet_id = ?1",
+        params![scriptlet_id],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Parses a UTC timestamp in SQLite's `YYYY-MM-DD HH:MM:SS` format, as written by
+/// `CURRENT_TIMESTAMP`. The time may be left out, meaning midnight. Years outside 1 to 9999, which
+/// SQLite can't represent either, are rejected.
+pub fn parse_timestamp(value: &str) -> Option<SystemTime> {
+    let value = value.trim();
+    let (date, time) = value
+        .split_once([' ', 'T'])
+        .map_or((value, None), |(date, time)| (date, Some(time)));
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    if !(1..=9999).contains(&year)
+        || !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+    {
+        return None;
+    }
+    let mut seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY;
+    if let Some(time) = time {
+        let mut parts = time.split(':').map(str::parse::<i64>);
+        let hours = parts.next()?.ok()?;
+        let minutes = parts.next().unwrap_or(Ok(0)).ok()?;
+        let secs = parts.next().unwrap_or(Ok(0)).ok()?;
+        if !(0..24).contains(&hours) || !(0..60).contains(&minutes) || !(0..60).contains(&secs) {
+            return None;
+        }
+        seconds += hours * 60 * 60 + minutes * 60 + secs;
+    }
+    Some(from_unix_seconds(seconds))
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Formats a time the way SQLite's `CURRENT_TIMESTAMP` does, in UTC.
+pub fn format_timestamp(time: SystemTime) -> String {
+    let seconds = unix_seconds(time);
+    let (days, secs) = (
+        seconds.div_euclid(SECONDS_PER_DAY),
+        seconds.rem_euclid(SECONDS_PER_DAY),
+    );
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+pub fn unix_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_secs() as i64,
+        Err(before) => -(before.duration().as_secs() as i64),
+    }
+}
+
+fn from_unix_seconds(seconds: i64) -> SystemTime {
+    if seconds >= 0 {
+        UNIX_EPOCH + Duration::from_secs(seconds as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+    }
+}
+
+// Conversions between days since 1970-01-01 and Gregorian dates, using Howard Hinnant's
+// `days_from_civil` and `civil_from_days` algorithms.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_timestamps() {
+        let time = parse_timestamp("2024-02-29 23:59:58").unwrap();
+        assert_eq!(format_timestamp(time), "2024-02-29 23:59:58");
+        assert_eq!(
+            format_timestamp(parse_timestamp("2025-03-01").unwrap()),
+            "2025-03-01 00:00:00"
+        );
+    }
+
+    #[test]
+    fn rejects_dates_and_times_out_of_range() {
+        for value in [
+            "2025-02-29",
+            "2025-02-31 99:99",
+            "2025-04-31",
+            "2025-03-01 24:00",
+            "2025-03-01 12:60",
+            "0-01-01",
+            "10000-01-01",
+            "99999999999999-01-01",
+        ] {
+            assert_eq!(parse_timestamp(value), None, "{value}");
+        }
+    }
+}
@@ -1,26 +1,99 @@
+use crate::database::data_types::ToolData;
 use crate::errors::error::DocuError;
-use crate::errors::error::DocuError::DatabaseSql;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension, params};
 use std::sync::MutexGuard;
 
 pub fn add_or_get_tool(name: &str, conn: &MutexGuard<Connection>) -> Result<i64, DocuError> {
-    if let Ok(existing_id) = get_tool_id(name, conn) {
+    if let Some(existing_id) = get_tool_id(name, conn)? {
         return Ok(existing_id);
     }
     insert_row(name, conn)
 }
 
-pub(crate) fn get_tool_id(name: &str, conn: &MutexGuard<Connection>) -> Result<i64, DocuError> {
+pub(crate) fn get_tool_id(
+    name: &str,
+    conn: &MutexGuard<Connection>,
+) -> Result<Option<i64>, DocuError> {
     let id = conn
         .query_row("SELECT id FROM tool WHERE name = ?", [name], |row| {
             row.get(0)
         })
-        .map_err(DatabaseSql)?;
+        .optional()?;
     Ok(id)
 }
 
 fn insert_row(name: &str, conn: &MutexGuard<Connection>) -> Result<i64, DocuError> {
-    conn.execute("INSERT INTO tool (name) VALUES (?)", [name])
-        .map_err(DatabaseSql)?;
+    conn.execute("INSERT INTO tool (name) VALUES (?)", [name])?;
     Ok(conn.last_insert_rowid())
 }
+
+/// Every tool with the number of scriptlets using it and when one of those was last used.
+/// Scriptlets in the trash are not counted.
+pub(crate) fn get_tools(conn: &MutexGuard<Connection>) -> Result<Vec<ToolData>, DocuError> {
+    let mut stmt = conn.prepare(
+        "SELECT t.name, COUNT(DISTINCT s.id), date(MAX(u.time))
+         FROM   tool t
+         LEFT   JOIN tool_scriptlet ts ON ts.tool_id = t.id
+         LEFT   JOIN scriptlet s ON s.id = ts.scriptlet_id AND s.deleted_at IS NULL
+         LEFT   JOIN usage u ON u.scriptlet_id = s.id
+         GROUP  BY t.id
+         ORDER  BY COUNT(DISTINCT s.id) DESC, t.name",
+    )?;
+    let tools = stmt
+        .query_map([], |row| {
+            Ok(ToolData {
+                name: row.get(0)?,
+                scriptlet_count: row.get(1)?,
+                last_used: row.get(2)?,
+            })
+        })?
+        .collect::<Result<_, _>>()
+        .map_err(DocuError::from)?;
+    Ok(tools)
+}
+
+pub(crate) fn rename_tool(
+    id: i64,
+    name: &str,
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    conn.execute("UPDATE tool SET name = ?2 WHERE id = ?1", params![id, name])?;
+    Ok(())
+}
+
+pub(crate) fn remove_tool(id: i64, conn: &MutexGuard<Connection>) -> Result<(), DocuError> {
+    conn.execute("DELETE FROM tool WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Deletes every tool that no scriptlet links to, including scriptlets in the trash, and
+/// returns their names.
+pub(crate) fn remove_orphaned_tools(
+    conn: &MutexGuard<Connection>,
+) -> Result<Vec<String>, DocuError> {
+    let mut stmt = conn.prepare(
+        "DELETE FROM tool
+         WHERE id NOT IN (SELECT tool_id FROM tool_scriptlet)
+         RETURNING name",
+    )?;
+    let mut names: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()
+        .map_err(DocuError::from)?;
+    names.sort();
+    Ok(names)
+}
+
+/// The names of every tool that no scriptlet links to, including scriptlets in the trash.
+pub(crate) fn get_orphaned_tools(conn: &MutexGuard<Connection>) -> Result<Vec<String>, DocuError> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM tool
+         WHERE id NOT IN (SELECT tool_id FROM tool_scriptlet)
+         ORDER BY name",
+    )?;
+    let names = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()
+        .map_err(DocuError::from)?;
+    Ok(names)
+}
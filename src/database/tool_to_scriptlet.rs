@@ -1,9 +1,6 @@
-use crate::database::data_types::ScriptletData;
-use crate::database::scriptlet::convert_to_scriptlet_data;
 use crate::errors::error::DocuError;
-use crate::errors::error::DocuError::DatabaseSql;
 use rusqlite::Connection;
-use rusqlite::{Row, params};
+use rusqlite::params;
 use std::sync::MutexGuard;
 
 pub fn link_scriptlet_to_tool(
@@ -22,30 +19,57 @@ fn insert_tool_scriptlet(
     conn.execute(
         "INSERT OR IGNORE INTO tool_scriptlet (tool_id, scriptlet_id) VALUES (?1, ?2)",
         params![tool_id, scriptlet_id],
-    )
-    .map_err(DatabaseSql)?;
+    )?;
     Ok(())
 }
 
-pub fn get_from_tool_id(
-    tool_id: i64,
+pub fn unlink_scriptlet(scriptlet_id: i64, conn: &MutexGuard<Connection>) -> Result<(), DocuError> {
+    conn.execute(
+        "DELETE FROM tool_scriptlet WHERE scriptlet_id = ?1",
+        params![scriptlet_id],
+    )?;
+    Ok(())
+}
+
+/// Moves every link from one tool to another, returning how many scriptlets were moved.
+/// Scriptlets already linked to both tools simply lose the first one.
+pub fn move_links(
+    from_tool_id: i64,
+    to_tool_id: i64,
     conn: &MutexGuard<Connection>,
-) -> Result<Vec<ScriptletData>, DocuError> {
-    let mut stmt = conn
-        .prepare(
-            "SELECT s.id, s.name, s.command, s.description
-             FROM scriptlet s
-             JOIN tool_scriptlet ts ON s.id = ts.scriptlet_id
-             WHERE ts.tool_id = ?1
-             ORDER BY s.time DESC",
-        )
-        .map_err(DatabaseSql)?;
+) -> Result<usize, DocuError> {
+    conn.execute(
+        "INSERT OR IGNORE INTO tool_scriptlet (tool_id, scriptlet_id)
+         SELECT ?2, scriptlet_id FROM tool_scriptlet WHERE tool_id = ?1",
+        params![from_tool_id, to_tool_id],
+    )?;
+    let moved = conn.execute(
+        "DELETE FROM tool_scriptlet WHERE tool_id = ?1",
+        params![from_tool_id],
+    )?;
+    Ok(moved)
+}
 
-    let rows = stmt
-        .query_map(params![tool_id], |row: &Row| convert_to_scriptlet_data(row))
-        .map_err(DatabaseSql)?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(DatabaseSql)?;
+/// Deletes links pointing at scriptlets or tools that no longer exist, returning how many
+/// were removed.
+pub fn remove_dangling_links(conn: &MutexGuard<Connection>) -> Result<usize, DocuError> {
+    let removed = conn.execute(
+        "DELETE FROM tool_scriptlet
+             WHERE scriptlet_id NOT IN (SELECT id FROM scriptlet)
+                OR tool_id NOT IN (SELECT id FROM tool)",
+        [],
+    )?;
+    Ok(removed)
+}
 
-    Ok(rows)
+/// The number of links pointing at scriptlets or tools that no longer exist.
+pub fn count_dangling_links(conn: &MutexGuard<Connection>) -> Result<usize, DocuError> {
+    let count = conn.query_row(
+        "SELECT COUNT(*) FROM tool_scriptlet
+             WHERE scriptlet_id NOT IN (SELECT id FROM scriptlet)
+                OR tool_id NOT IN (SELECT id FROM tool)",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count)
 }
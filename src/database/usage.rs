@@ -0,0 +1,24 @@
+use crate::database::data_types::UsageKind;
+use crate::errors::error::DocuError;
+use rusqlite::{Connection, params};
+use std::sync::MutexGuard;
+
+pub(crate) fn insert_usage(
+    scriptlet_id: i64,
+    kind: UsageKind,
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    conn.execute(
+        "INSERT INTO usage (scriptlet_id, kind) VALUES (?1, ?2)",
+        params![scriptlet_id, kind.as_str()],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn remove_usage(
+    scriptlet_id: i64,
+    conn: &MutexGuard<Connection>,
+) -> Result<(), DocuError> {
+    conn.execute("DELETE FROM usage WHERE scriptlet_id = ?1", [scriptlet_id])?;
+    Ok(())
+}
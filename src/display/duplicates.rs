@@ -0,0 +1,30 @@
+use crate::cli::exit::or_exit;
+use crate::parse::similarity::{normalize_command, similarity};
+use crate::tui::print::show_duplicate_pairs_tui;
+use docu::database::data_types::ScriptletFilter;
+use docu::store::scriptlet_store::ScriptletStore;
+
+/// Compares every pair of scriptlets and shows those whose commands are at least `threshold`
+/// similar, most similar first.
+pub fn show_duplicates(store: &dyn ScriptletStore, threshold: f64) {
+    let scriptlets = or_exit(store.list(&ScriptletFilter::default()));
+    let normalized: Vec<Vec<String>> = scriptlets
+        .iter()
+        .map(|s| normalize_command(&s.command))
+        .collect();
+    let mut pairs = Vec::new();
+    for i in 0..scriptlets.len() {
+        for j in i + 1..scriptlets.len() {
+            let score = similarity(&normalized[i], &normalized[j]);
+            if score >= threshold {
+                pairs.push((&scriptlets[i], &scriptlets[j], score));
+            }
+        }
+    }
+    if pairs.is_empty() {
+        println!("No duplicate scriptlets found");
+        return;
+    }
+    pairs.sort_by(|a, b| b.2.total_cmp(&a.2));
+    show_duplicate_pairs_tui(&pairs);
+}
@@ -0,0 +1,12 @@
+use crate::cli::exit::or_exit;
+use crate::tui::print::show_history_tui;
+use docu::store::scriptlet_store::ScriptletStore;
+
+pub fn show_scriptlet_history(store: &dyn ScriptletStore, id: i64) {
+    let Some(scriptlet) = or_exit(store.get(id)) else {
+        println!("No scriptlet found with id {id}");
+        return;
+    };
+    let revisions = or_exit(store.revisions(id));
+    show_history_tui(scriptlet, revisions);
+}
@@ -0,0 +1,38 @@
+use crate::cli::exit::or_exit;
+use docu::database::migrate::latest_version;
+use docu::store::sqlite::SqliteStore;
+
+pub fn show_migration_status(store: &SqliteStore) {
+    let status = or_exit(store.migration_status());
+    println!(
+        "Schema version {} (latest {})",
+        status.current_version,
+        latest_version()
+    );
+    for migration in status.applied {
+        println!(
+            "  applied  {:>3}  {}",
+            migration.version, migration.description
+        );
+    }
+    for migration in status.pending {
+        println!(
+            "  pending  {:>3}  {}",
+            migration.version, migration.description
+        );
+    }
+}
+
+pub fn apply_migrations(store: &SqliteStore) {
+    let applied = or_exit(store.migrate());
+    if applied.is_empty() {
+        println!("Database is already up to date");
+        return;
+    }
+    for migration in applied {
+        println!(
+            "Applied migration {}: {}",
+            migration.version, migration.description
+        );
+    }
+}
@@ -1 +1,6 @@
+pub mod duplicates;
+pub mod history;
+pub mod migrations;
 pub mod scriptlets;
+pub mod tools;
+pub mod trash;
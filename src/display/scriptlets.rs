@@ -1,21 +1,56 @@
-use crate::database::connect::{get_all_scriptlets, get_scriptlets_for_tool, search_scriptlets};
+use crate::action::fill::{fill_command, placeholder_values};
+use crate::cli::exit::or_exit;
 use crate::tui::print::show_all_scriptlets_tui;
+use crate::tui::select::select_scriptlet_tui;
+use docu::database::data_types::{ScriptletData, ScriptletFilter, SortOrder, TimeRange, UsageKind};
+use docu::store::scriptlet_store::ScriptletStore;
 
-pub fn show_all_scriptlets() {
-    let scriplets = get_all_scriptlets().expect("can't get scriptlets");
-    show_all_scriptlets_tui(scriplets);
-}
-
-pub fn show_all_scriptlets_for_tool(tool_name: &str) {
-    let scriplets = get_scriptlets_for_tool(tool_name).expect("can't get scriptlets");
+pub fn show_scriptlets(store: &dyn ScriptletStore, filter: &ScriptletFilter, select: bool) {
+    let scriplets = or_exit(store.list(filter));
     if scriplets.is_empty() {
-        println!("No scriptlets found for {tool_name}");
+        match (&filter.tool, &filter.tag) {
+            (Some(tool_name), None) => println!("No scriptlets found for {tool_name}"),
+            (None, Some(tag)) => println!("No scriptlets found tagged {tag}"),
+            (Some(tool_name), Some(tag)) => {
+                println!("No scriptlets found for {tool_name} tagged {tag}")
+            }
+            (None, None) => {}
+        }
         return;
     }
-    show_all_scriptlets_tui(scriplets);
+    display(store, scriplets, select);
 }
 
-pub fn show_searched_scriptlets(search: &[String]) {
-    let scriplets = search_scriptlets(&search.join(" ")).expect("can't get scriptlets");
-    show_all_scriptlets_tui(scriplets);
+pub fn show_searched_scriptlets(
+    store: &dyn ScriptletStore,
+    search: &[String],
+    sort: Option<SortOrder>,
+    created: &TimeRange,
+    select: bool,
+) {
+    let scriplets = or_exit(store.search(&search.join(" "), sort, created));
+    display(store, scriplets, select);
+}
+
+/// Either lists the scriptlets, or lets the user pick one and prints its command on its own so
+/// it can be used from the shell, e.g. `$(docu search --select docker)`, with its placeholders
+/// filled in.
+fn display(store: &dyn ScriptletStore, scriptlets: Vec<ScriptletData>, select: bool) {
+    if !select {
+        show_all_scriptlets_tui(scriptlets);
+        return;
+    }
+    if scriptlets.is_empty() {
+        println!("No scriptlets found");
+        return;
+    }
+    if let Some(scriptlet) = select_scriptlet_tui(&scriptlets) {
+        if let Err(err) = store.record_usage(scriptlet.id, UsageKind::Select) {
+            eprintln!("Usage not recorded: {err}");
+        }
+        println!(
+            "{}",
+            fill_command(&scriptlet.command, &placeholder_values(scriptlet))
+        );
+    }
 }
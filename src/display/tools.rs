@@ -0,0 +1,12 @@
+use crate::cli::exit::or_exit;
+use crate::tui::print::show_tools_tui;
+use docu::store::scriptlet_store::ScriptletStore;
+
+pub fn show_tools(store: &dyn ScriptletStore) {
+    let tools = or_exit(store.tools());
+    if tools.is_empty() {
+        println!("No tools found");
+        return;
+    }
+    show_tools_tui(tools);
+}
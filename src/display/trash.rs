@@ -0,0 +1,12 @@
+use crate::cli::exit::or_exit;
+use crate::tui::print::show_trashed_scriptlets_tui;
+use docu::store::scriptlet_store::ScriptletStore;
+
+pub fn show_trash(store: &dyn ScriptletStore) {
+    let trashed = or_exit(store.trashed());
+    if trashed.is_empty() {
+        println!("The trash is empty");
+        return;
+    }
+    show_trashed_scriptlets_tui(trashed);
+}
@@ -0,0 +1,71 @@
+use crate::cli::exit::or_exit;
+use dialoguer::Confirm;
+use dialoguer::theme::ColorfulTheme;
+use docu::database::data_types::DoctorReport;
+use docu::store::sqlite::SqliteStore;
+
+pub fn run_doctor(store: &SqliteStore, yes: bool) {
+    let report = or_exit(store.check());
+    print_report(&report);
+    if report.is_healthy() {
+        println!("No problems found");
+        return;
+    }
+    if !report.integrity_errors.is_empty() {
+        println!(
+            "The database file is damaged. Restore it from a backup with `docu restore <file>`"
+        );
+    }
+    if !report.index_out_of_sync() && !report.links_broken() {
+        return;
+    }
+    if !yes
+        && !Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Rebuild the search index and repair tool links?")
+            .default(true)
+            .interact()
+            .expect("Failed to read confirmation")
+    {
+        println!("Repair cancelled");
+        return;
+    }
+    let report = or_exit(store.repair().and_then(|()| store.check()));
+    if !report.index_out_of_sync() && !report.links_broken() {
+        println!("Repaired the search index and tool links");
+    } else {
+        println!("Some problems remain after the repair");
+        print_report(&report);
+    }
+}
+
+fn print_report(report: &DoctorReport) {
+    if report.integrity_errors.is_empty() {
+        println!("Database file:  ok");
+    } else {
+        println!(
+            "Database file:  {} problem(s)",
+            report.integrity_errors.len()
+        );
+        for error in &report.integrity_errors {
+            println!("  {error}");
+        }
+    }
+    if report.index_out_of_sync() {
+        println!(
+            "Search index:   {} scriptlet(s) missing, {} stale entry(s)",
+            report.unindexed.len(),
+            report.stale_index.len()
+        );
+    } else {
+        println!("Search index:   ok");
+    }
+    if report.links_broken() {
+        println!(
+            "Tool links:     {} dangling link(s), {} unused tool(s)",
+            report.dangling_links,
+            report.orphaned_tools.len()
+        );
+    } else {
+        println!("Tool links:     ok");
+    }
+}
@@ -0,0 +1,87 @@
+use crate::cli::exit::or_exit;
+use crate::parse::tools::extract_tools;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Editor};
+use docu::database::data_types::{NewScriptlet, ScriptletData};
+use docu::store::scriptlet_store::ScriptletStore;
+
+const NAME_HEADER: &str = "# Name";
+const DESCRIPTION_HEADER: &str = "# Description";
+const COMMAND_HEADER: &str = "# Command";
+
+pub fn edit_scriptlet(store: &dyn ScriptletStore, id: i64) {
+    let Some(scriptlet) = or_exit(store.get(id)) else {
+        println!("No scriptlet found with id {id}");
+        return;
+    };
+    let mut document = to_document(&scriptlet);
+    let (name, description, command) = loop {
+        let Some(edited) = Editor::new()
+            .extension(".md")
+            .edit(&document)
+            .expect("Failed to open editor")
+        else {
+            println!("Edit aborted, no changes made");
+            return;
+        };
+        let Some((name, description, command)) = from_document(&edited) else {
+            println!("Name, description and command must all be present, no changes made");
+            return;
+        };
+        match or_exit(store.find_by_name(&name)) {
+            Some(taken) if taken != id => {
+                println!("A scriptlet named {name} already exists ({taken})");
+                if !Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Edit the scriptlet again?")
+                    .default(true)
+                    .interact()
+                    .expect("Failed to read confirmation")
+                {
+                    println!("No changes made");
+                    return;
+                }
+                document = edited;
+            }
+            _ => break (name, description, command),
+        }
+    };
+    if name == scriptlet.name
+        && description == scriptlet.description
+        && command == scriptlet.command
+    {
+        println!("No changes made");
+        return;
+    }
+    let tools = extract_tools(&command);
+    let edited = NewScriptlet::new(name, &command)
+        .description(description)
+        .tools(tools);
+    or_exit(store.update(id, &edited));
+}
+
+fn to_document(scriptlet: &ScriptletData) -> String {
+    format!(
+        "{NAME_HEADER}\n{}\n\n{DESCRIPTION_HEADER}\n{}\n\n{COMMAND_HEADER}\n{}\n",
+        scriptlet.name, scriptlet.description, scriptlet.command
+    )
+}
+
+/// Splits an edited document back into its name, description and command. The command section
+/// is always last, so everything after its last header belongs to the command, which keeps its
+/// indentation and only loses the trailing newlines.
+fn from_document(document: &str) -> Option<(String, String, String)> {
+    let (rest, command) = document.rsplit_once(&format!("\n{COMMAND_HEADER}\n"))?;
+    let (name, description) = rest
+        .trim_start()
+        .strip_prefix(&format!("{NAME_HEADER}\n"))?
+        .split_once(&format!("\n{DESCRIPTION_HEADER}\n"))?;
+    let fields = (
+        name.trim().to_string(),
+        description.trim().to_string(),
+        command.trim_end_matches(['\n', '\r']).to_string(),
+    );
+    if fields.0.is_empty() || fields.1.is_empty() || fields.2.trim().is_empty() {
+        return None;
+    }
+    Some(fields)
+}
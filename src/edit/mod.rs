@@ -0,0 +1,6 @@
+pub mod doctor;
+pub mod editor;
+pub mod revert;
+pub mod tags;
+pub mod tools;
+pub mod trash;
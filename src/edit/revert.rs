@@ -0,0 +1,23 @@
+use crate::cli::exit::or_exit;
+use crate::parse::tools::extract_tools;
+use docu::database::data_types::NewScriptlet;
+use docu::store::scriptlet_store::ScriptletStore;
+
+pub fn revert_scriptlet(store: &dyn ScriptletStore, id: i64, revision: i64) {
+    if or_exit(store.get(id)).is_none() {
+        println!("No scriptlet found with id {id}");
+        return;
+    }
+    let Some(old) = or_exit(store.revisions(id))
+        .into_iter()
+        .find(|old| old.revision == revision)
+    else {
+        println!("Scriptlet {id} has no revision {revision}");
+        return;
+    };
+    let reverted = NewScriptlet::new(&old.name, &old.command)
+        .description(&old.description)
+        .tools(extract_tools(&old.command));
+    or_exit(store.update(id, &reverted));
+    println!("Reverted scriptlet {id} to revision {revision}");
+}
@@ -0,0 +1,25 @@
+use crate::cli::exit::or_exit;
+use crate::parse::parser::normalize_tags;
+use docu::store::scriptlet_store::ScriptletStore;
+
+pub fn add_tags(store: &dyn ScriptletStore, id: i64, tags: &[String]) {
+    if !scriptlet_exists(store, id) {
+        return;
+    }
+    or_exit(store.tag(id, &normalize_tags(tags)));
+}
+
+pub fn remove_tags(store: &dyn ScriptletStore, id: i64, tags: &[String]) {
+    if !scriptlet_exists(store, id) {
+        return;
+    }
+    or_exit(store.untag(id, &normalize_tags(tags)));
+}
+
+fn scriptlet_exists(store: &dyn ScriptletStore, id: i64) -> bool {
+    let exists = or_exit(store.get(id)).is_some();
+    if !exists {
+        println!("No scriptlet found with id {id}");
+    }
+    exists
+}
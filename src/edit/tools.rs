@@ -0,0 +1,30 @@
+use crate::cli::exit::or_exit;
+use docu::store::scriptlet_store::ScriptletStore;
+
+pub fn prune_orphaned_tools(store: &dyn ScriptletStore) {
+    let (links, tools) = or_exit(store.prune_tools());
+    if links == 0 && tools.is_empty() {
+        println!("No orphaned tools found");
+        return;
+    }
+    if links > 0 {
+        println!("Removed {links} link(s) to scriptlets that no longer exist");
+    }
+    if !tools.is_empty() {
+        println!(
+            "Removed {} unused tool(s): {}",
+            tools.len(),
+            tools.join(", ")
+        );
+    }
+}
+
+pub fn merge_tool(store: &dyn ScriptletStore, from: &str, into: &str) {
+    let moved = or_exit(store.merge_tools(from, into));
+    println!("Merged {from} into {into}, moving {moved} scriptlet(s)");
+}
+
+pub fn rename_existing_tool(store: &dyn ScriptletStore, from: &str, to: &str) {
+    or_exit(store.rename_tool(from, to));
+    println!("Renamed {from} to {to}");
+}
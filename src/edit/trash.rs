@@ -0,0 +1,47 @@
+use crate::cli::exit::or_exit;
+use docu::store::scriptlet_store::ScriptletStore;
+use std::time::Duration;
+
+pub fn remove_scriptlets(store: &dyn ScriptletStore, ids: &[i64]) {
+    let trashed = or_exit(store.remove(ids));
+    report_missing(ids, &trashed);
+    if !trashed.is_empty() {
+        println!(
+            "Moved {} to the trash. Undo with `docu trash restore {}`",
+            describe(&trashed),
+            join_ids(&trashed)
+        );
+    }
+}
+
+pub fn restore_from_trash(store: &dyn ScriptletStore, ids: &[i64]) {
+    let restored = or_exit(store.restore(ids));
+    for id in ids.iter().filter(|id| !restored.contains(id)) {
+        println!("No scriptlet with id {id} in the trash");
+    }
+    if !restored.is_empty() {
+        println!("Restored {}", describe(&restored));
+    }
+}
+
+pub fn empty_trash_older_than(store: &dyn ScriptletStore, older_than: Option<Duration>) {
+    let deleted = or_exit(store.empty_trash(older_than.unwrap_or_default()));
+    println!("Permanently deleted {deleted} scriptlet(s) from the trash");
+}
+
+fn report_missing(requested: &[i64], found: &[i64]) {
+    for id in requested.iter().filter(|id| !found.contains(id)) {
+        println!("No scriptlet found with id {id}");
+    }
+}
+
+fn describe(ids: &[i64]) -> String {
+    match ids {
+        [id] => format!("scriptlet {id}"),
+        _ => format!("scriptlets {}", join_ids(ids)),
+    }
+}
+
+fn join_ids(ids: &[i64]) -> String {
+    ids.iter().map(i64::to_string).collect::<Vec<_>>().join(" ")
+}
@@ -1,13 +1,45 @@
+use rusqlite::ErrorCode;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum DocuError {
     #[error("Database errors: {0}")]
-    DatabaseSql(#[from] rusqlite::Error),
+    DatabaseSql(rusqlite::Error),
+
+    #[error("Database is locked: {0}")]
+    Locked(String),
+
+    #[error("Backup errors: {0}")]
+    Backup(String),
+
+    #[error("Tool errors: {0}")]
+    Tool(String),
 
     #[error("Access errors: {0}")]
     Access(String),
 
+    #[error("Markdown library errors: {0}")]
+    Markdown(String),
+
+    #[error("Configuration errors: {0}")]
+    Config(String),
+
+    #[error("Migration errors: {0}")]
+    Migration(String),
+
     #[error("I/O errors: {0}")]
     Io(#[from] std::io::Error),
 }
+
+/// Lock contention is reported separately from other SQLite failures, as it only means another
+/// docu process held the database for longer than the busy timeout.
+impl From<rusqlite::Error> for DocuError {
+    fn from(err: rusqlite::Error) -> Self {
+        match err.sqlite_error_code() {
+            Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => DocuError::Locked(
+                "another docu process is using the database, try again in a moment".to_string(),
+            ),
+            _ => DocuError::DatabaseSql(err),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::import::history::HistoryEntry;
+use rusqlite::{Connection, OpenFlags, Row};
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Reads the commands in an Atuin database that succeeded, oldest first. Atuin records times in
+/// nanoseconds, and the host as `host:user`.
+pub fn read_atuin(path: &Path) -> rusqlite::Result<Vec<HistoryEntry>> {
+    let conn = open(path)?;
+    // Older Atuin databases removed deleted commands rather than marking them.
+    let not_deleted = if has_column(&conn, "history", "deleted_at")? {
+        "AND deleted_at IS NULL"
+    } else {
+        ""
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT command, timestamp, cwd, hostname FROM history
+         WHERE exit = 0 {not_deleted}
+         ORDER BY timestamp"
+    ))?;
+    stmt.query_map([], |row| {
+        let host: Option<String> = known(row, 3)?;
+        Ok(HistoryEntry {
+            command: row.get(0)?,
+            time: Some(UNIX_EPOCH + Duration::from_nanos(row.get::<_, i64>(1)?.max(0) as u64)),
+            cwd: known(row, 2)?,
+            host: host.map(|host| match host.split_once(':') {
+                Some((name, _)) => name.to_string(),
+                None => host,
+            }),
+        })
+    })?
+    .collect()
+}
+
+/// Reads the commands in a McFly database that succeeded, oldest first.
+pub fn read_mcfly(path: &Path) -> rusqlite::Result<Vec<HistoryEntry>> {
+    let conn = open(path)?;
+    let mut stmt = conn.prepare(
+        "SELECT cmd, when_run, dir FROM commands
+         WHERE exit_code = 0
+         ORDER BY when_run, id",
+    )?;
+    stmt.query_map([], |row| {
+        Ok(HistoryEntry {
+            command: row.get(0)?,
+            time: row
+                .get::<_, Option<i64>>(1)?
+                .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds.max(0) as u64)),
+            cwd: known(row, 2)?,
+            host: None,
+        })
+    })?
+    .collect()
+}
+
+/// Opens a history database without changing it, even while the tool it belongs to is writing
+/// to it.
+fn open(path: &Path) -> rusqlite::Result<Connection> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    conn.busy_timeout(Duration::from_secs(5))?;
+    Ok(conn)
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+        [table, column],
+        |row| row.get(0),
+    )
+}
+
+/// A text column that may be empty or null when the tool didn't know the value.
+fn known(row: &Row, index: usize) -> rusqlite::Result<Option<String>> {
+    Ok(row
+        .get::<_, Option<String>>(index)?
+        .filter(|value| !value.is_empty()))
+}
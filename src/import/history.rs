@@ -0,0 +1,187 @@
+use crate::import::databases::{read_atuin, read_mcfly};
+use crate::import::shells::{parse_bash, parse_fish, parse_zsh};
+use crate::parse::commands::simple_commands;
+use crate::parse::tools::{program_name, unwrap_command};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::{env, fs, io};
+
+/// Commands that are run all the time but aren't worth keeping as scriptlets on their own.
+const TRIVIAL_COMMANDS: [&str; 25] = [
+    "cd", "ls", "ll", "la", "l", "pwd", "clear", "exit", "logout", "history", "fg", "bg", "jobs",
+    "man", "which", "type", "cat", "less", "more", "vi", "vim", "nvim", "nano", "source", "docu",
+];
+
+/// Where commands can be imported from: a shell's history file, or the database of a tool that
+/// records every command run along with where it was run and whether it succeeded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistorySource {
+    Bash,
+    Zsh,
+    Fish,
+    Atuin,
+    McFly,
+}
+
+impl HistorySource {
+    pub const ALL: [HistorySource; 5] = [
+        HistorySource::Bash,
+        HistorySource::Zsh,
+        HistorySource::Fish,
+        HistorySource::Atuin,
+        HistorySource::McFly,
+    ];
+
+    /// Whether this is a database, which holds the same commands as the shell history files but
+    /// knows more about them.
+    pub fn is_database(&self) -> bool {
+        matches!(self, HistorySource::Atuin | HistorySource::McFly)
+    }
+
+    /// Where the history is kept unless the shell was configured otherwise.
+    pub fn default_path(&self) -> Option<PathBuf> {
+        match self {
+            HistorySource::Bash => dirs::home_dir().map(|home| home.join(".bash_history")),
+            HistorySource::Zsh => env::var_os("ZDOTDIR")
+                .map(PathBuf::from)
+                .or_else(dirs::home_dir)
+                .map(|dir| dir.join(".zsh_history")),
+            HistorySource::Fish => {
+                xdg_data_dir().map(|data| data.join("fish").join("fish_history"))
+            }
+            HistorySource::Atuin => {
+                xdg_data_dir().map(|data| data.join("atuin").join("history.db"))
+            }
+            HistorySource::McFly => {
+                let legacy = dirs::home_dir().map(|home| home.join(".mcfly").join("history.db"));
+                let name = if cfg!(target_os = "macos") {
+                    "McFly"
+                } else {
+                    "mcfly"
+                };
+                legacy
+                    .filter(|path| path.is_file())
+                    .or_else(|| dirs::data_dir().map(|data| data.join(name).join("history.db")))
+            }
+        }
+    }
+
+    /// Works out what wrote a history file from its path, or failing that the start of its
+    /// contents. Plain zsh history looks the same as bash history, so it is read as bash history,
+    /// and databases are taken to be Atuin's unless they are in a McFly directory.
+    pub fn guess(path: &Path, start: &[u8]) -> HistorySource {
+        let name = path.to_string_lossy().to_lowercase();
+        if start.starts_with(b"SQLite format 3\0") {
+            if name.contains("mcfly") {
+                HistorySource::McFly
+            } else {
+                HistorySource::Atuin
+            }
+        } else if name.contains("fish") || start.starts_with(b"- cmd: ") {
+            HistorySource::Fish
+        } else if name.contains("zsh") || start.starts_with(b": ") {
+            HistorySource::Zsh
+        } else {
+            HistorySource::Bash
+        }
+    }
+}
+
+/// A command read from a history, with when, where and on which host it was run, as far as the
+/// history records that.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub command: String,
+    pub time: Option<SystemTime>,
+    pub cwd: Option<String>,
+    pub host: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn new(command: impl Into<String>, time: Option<SystemTime>) -> Self {
+        Self {
+            command: command.into(),
+            time,
+            cwd: None,
+            host: None,
+        }
+    }
+}
+
+/// Reads the commands in a history file, oldest first. Only the commands that succeeded are read
+/// from databases, as shell history files don't record that.
+pub fn read_history(source: HistorySource, path: &Path) -> io::Result<Vec<HistoryEntry>> {
+    match source {
+        HistorySource::Atuin => return read_atuin(path).map_err(io::Error::other),
+        HistorySource::McFly => return read_mcfly(path).map_err(io::Error::other),
+        _ => {}
+    }
+    let bytes = fs::read(path)?;
+    Ok(match source {
+        HistorySource::Zsh => parse_zsh(&bytes),
+        HistorySource::Fish => parse_fish(&String::from_utf8_lossy(&bytes)),
+        _ => parse_bash(&String::from_utf8_lossy(&bytes)),
+    })
+}
+
+/// A command worth offering for import: its first run, and how many times it was run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportCandidate {
+    pub entry: HistoryEntry,
+    pub count: usize,
+}
+
+/// Turns a history, oldest first, into the commands worth offering for import. Trivial commands
+/// are left out, and each command is offered once. The most often run commands come first, then
+/// the most recently run.
+pub fn import_candidates(entries: Vec<HistoryEntry>) -> Vec<ImportCandidate> {
+    let mut candidates: Vec<(usize, ImportCandidate)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (position, mut entry) in entries.into_iter().enumerate() {
+        entry.command = entry.command.trim().to_string();
+        if is_trivial(&entry.command) {
+            continue;
+        }
+        match index.get(&entry.command) {
+            Some(&i) => {
+                let (last, candidate) = &mut candidates[i];
+                *last = position;
+                candidate.count += 1;
+            }
+            None => {
+                index.insert(entry.command.clone(), candidates.len());
+                candidates.push((position, ImportCandidate { entry, count: 1 }));
+            }
+        }
+    }
+    candidates.sort_by(|(a_last, a), (b_last, b)| b.count.cmp(&a.count).then(b_last.cmp(a_last)));
+    candidates
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Whether a command is too simple to be worth keeping: a single program without arguments, or
+/// a single everyday command such as `ls` or `cd`.
+fn is_trivial(command: &str) -> bool {
+    match simple_commands(command).as_slice() {
+        [] => true,
+        [only] => {
+            let words = unwrap_command(&only.words);
+            words.len() < 2
+                || words
+                    .first()
+                    .is_some_and(|program| TRIVIAL_COMMANDS.contains(&program_name(program)))
+        }
+        _ => false,
+    }
+}
+
+/// Where fish, Atuin and others keep their data, which is `~/.local/share` on macOS too.
+fn xdg_data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("share")))
+}
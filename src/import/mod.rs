@@ -0,0 +1,3 @@
+pub mod databases;
+pub mod history;
+pub mod shells;
@@ -0,0 +1,103 @@
+use crate::import::history::HistoryEntry;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Reads a bash history file. Timestamps are only there if `HISTTIMEFORMAT` was set, as `#`
+/// lines before each command.
+pub fn parse_bash(text: &str) -> Vec<HistoryEntry> {
+    let mut entries = Vec::new();
+    let mut time = None;
+    for line in text.lines() {
+        if let Some(seconds) = line.strip_prefix('#').and_then(|t| t.parse().ok()) {
+            time = Some(unix_time(seconds));
+        } else if !line.trim().is_empty() {
+            entries.push(HistoryEntry::new(line, time.take()));
+        }
+    }
+    entries
+}
+
+/// Reads a zsh history file, in either the plain format or the extended `: <time>:<duration>;`
+/// format. Commands spanning several lines have a `\` at the end of every line but the last.
+pub fn parse_zsh(bytes: &[u8]) -> Vec<HistoryEntry> {
+    let text = unmetafy(bytes);
+    let mut entries = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let (time, command) = match extended_zsh_line(line) {
+            Some((time, command)) => (Some(time), command),
+            None => (None, line),
+        };
+        let mut command = command.to_string();
+        while command.ends_with('\\')
+            && let Some(next) = lines.next()
+        {
+            command.pop();
+            command.push('\n');
+            command.push_str(next);
+        }
+        if !command.trim().is_empty() {
+            entries.push(HistoryEntry::new(command, time));
+        }
+    }
+    entries
+}
+
+fn extended_zsh_line(line: &str) -> Option<(SystemTime, &str)> {
+    let (meta, command) = line.strip_prefix(": ")?.split_once(';')?;
+    let (seconds, _duration) = meta.split_once(':')?;
+    Some((unix_time(seconds.trim().parse().ok()?), command))
+}
+
+/// Undoes the way zsh stores bytes it treats specially in its history file: as 0x83 followed by
+/// the byte with bit 5 flipped.
+fn unmetafy(bytes: &[u8]) -> String {
+    let mut unmetafied = Vec::with_capacity(bytes.len());
+    let mut bytes = bytes.iter();
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            0x83 => unmetafied.extend(bytes.next().map(|next| next ^ 0x20)),
+            _ => unmetafied.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&unmetafied).into_owned()
+}
+
+/// Reads a fish history file, which lists each command as `- cmd: ...` followed by the time it
+/// was run as `when: ...`.
+pub fn parse_fish(text: &str) -> Vec<HistoryEntry> {
+    let mut entries: Vec<HistoryEntry> = Vec::new();
+    for line in text.lines() {
+        if let Some(command) = line.strip_prefix("- cmd: ") {
+            entries.push(HistoryEntry::new(unescape_fish(command), None));
+        } else if let Some(seconds) = line.trim_start().strip_prefix("when: ")
+            && let Some(entry) = entries.last_mut()
+        {
+            entry.time = seconds.trim().parse().ok().map(unix_time);
+        }
+    }
+    entries
+}
+
+/// Fish escapes backslashes and newlines in the commands it stores.
+fn unescape_fish(command: &str) -> String {
+    let mut unescaped = String::with_capacity(command.len());
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                unescaped.push('\n');
+            }
+            ('\\', Some('\\')) => {
+                chars.next();
+                unescaped.push('\\');
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+fn unix_time(seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
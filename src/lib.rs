@@ -0,0 +1,8 @@
+//! The library behind the `docu` command line tool. Scriptlets are read and written through the
+//! [`store::scriptlet_store::ScriptletStore`] trait, opened with
+//! [`store::scriptlet_store::open_store`] once a database has been chosen with
+//! [`database::location::select_database`].
+pub mod config;
+pub mod database;
+pub mod errors;
+pub mod store;
@@ -1,40 +1,171 @@
-use crate::cli::command::{Cli, Commands};
-use crate::config::generate::generate_config_file;
-use crate::database::connect::remove_scriptlets;
-use crate::display::scriptlets::{
-    show_all_scriptlets, show_all_scriptlets_for_tool, show_searched_scriptlets,
-};
-use crate::parse::parser::parse_scriptlet;
+use crate::action::backup::{backup_to_file, restore_from_file};
+use crate::action::copy::copy_scriptlet;
+use crate::action::import::import_history;
+use crate::action::run::run_scriptlet;
+use crate::cli::command::{Cli, Commands, DbCommands, TagCommands, ToolsCommands, TrashCommands};
+use crate::cli::exit::or_exit;
+use crate::display::duplicates::show_duplicates;
+use crate::display::history::show_scriptlet_history;
+use crate::display::migrations::{apply_migrations, show_migration_status};
+use crate::display::scriptlets::{show_scriptlets, show_searched_scriptlets};
+use crate::display::tools::show_tools;
+use crate::display::trash::show_trash;
+use crate::edit::doctor::run_doctor;
+use crate::edit::editor::edit_scriptlet;
+use crate::edit::revert::revert_scriptlet;
+use crate::edit::tags::{add_tags, remove_tags};
+use crate::edit::tools::{merge_tool, prune_orphaned_tools, rename_existing_tool};
+use crate::edit::trash::{empty_trash_older_than, remove_scriptlets, restore_from_trash};
+use crate::parse::input::read_command;
+use crate::parse::parser::{AddOptions, parse_scriptlet};
 use clap::Parser;
+use docu::config::generate::generate_config_file;
+use docu::database::data_types::{ScriptletFilter, TimeRange};
+use docu::database::location::{database_path, select_database};
+use docu::store::scriptlet_store::open_store;
+use docu::store::sqlite::SqliteStore;
+use std::path::PathBuf;
+use std::process::exit;
 
+mod action;
 mod cli;
-mod config;
-mod database;
 mod display;
-mod errors;
+mod edit;
+mod import;
 mod parse;
 mod tui;
 
 fn main() {
     let cli = Cli::parse();
+    or_exit(select_database(cli.db, cli.library.as_deref()));
+    if database_path().is_dir() && !cli.command.supports_markdown_library() {
+        eprintln!(
+            "{} is a Markdown library, which doesn't support this command",
+            database_path().display()
+        );
+        exit(1);
+    }
+    let store = or_exit(open_store());
+    let store = store.as_ref();
     match cli.command {
-        Commands::Add { args } => {
-            if args.is_empty() {
-                println!("No scriptlet specified");
-                return;
+        Commands::Add {
+            args,
+            file,
+            stdin,
+            name,
+            description,
+            tools,
+            tags,
+            no_placeholders,
+            on_conflict,
+            source,
+        } => {
+            let file = file.or(stdin.then(|| PathBuf::from("-")));
+            let command = match read_command(args, file.as_deref()) {
+                Ok(Some(command)) if !command.trim().is_empty() => command,
+                Ok(_) => {
+                    println!("No scriptlet specified");
+                    return;
+                }
+                Err(err) => {
+                    eprintln!("Could not read the scriptlet: {err}");
+                    exit(1);
+                }
+            };
+            let options = AddOptions {
+                name,
+                description,
+                tools,
+                tags,
+                no_placeholders,
+                on_conflict: on_conflict.map(Into::into),
+                source_url: source,
+                ..AddOptions::default()
+            };
+            if let Err(err) = parse_scriptlet(store, &command, options) {
+                eprintln!("{err}");
+                exit(1);
             }
-            parse_scriptlet(&args);
         }
-        Commands::Show { tool } => match tool {
-            None => show_all_scriptlets(),
-            Some(tool_name) => show_all_scriptlets_for_tool(&tool_name),
-        },
-        Commands::Search { search } => {
-            show_searched_scriptlets(&search);
+        Commands::Show {
+            tool,
+            tag,
+            since,
+            before,
+            sort,
+            select,
+        } => {
+            let tag = tag.map(|tag| tag.trim_start_matches('#').to_lowercase());
+            let filter = ScriptletFilter {
+                tool,
+                tag,
+                created: TimeRange { since, before },
+                sort: sort.into(),
+            };
+            show_scriptlets(store, &filter, select);
+        }
+        Commands::Search {
+            search,
+            sort,
+            since,
+            before,
+            select,
+        } => {
+            let created = TimeRange { since, before };
+            show_searched_scriptlets(store, &search, sort.map(Into::into), &created, select);
+        }
+        Commands::Copy { id } => {
+            copy_scriptlet(store, id);
+        }
+        Commands::Run { id } => {
+            run_scriptlet(store, id);
+        }
+        Commands::Edit { id } => {
+            edit_scriptlet(store, id);
+        }
+        Commands::History { id } => {
+            show_scriptlet_history(store, id);
+        }
+        Commands::Revert { id, revision } => {
+            revert_scriptlet(store, id, revision);
         }
         Commands::Remove { ids } => {
-            remove_scriptlets(ids).expect("Failed to remove scriptlets");
+            remove_scriptlets(store, &ids);
         }
+        Commands::Trash { command } => match command {
+            TrashCommands::List => show_trash(store),
+            TrashCommands::Restore { ids } => restore_from_trash(store, &ids),
+            TrashCommands::Empty { older_than } => empty_trash_older_than(store, older_than),
+        },
+        Commands::Tools { command } => match command {
+            None => show_tools(store),
+            Some(ToolsCommands::Prune) => prune_orphaned_tools(store),
+            Some(ToolsCommands::Merge { from, into }) => merge_tool(store, &from, &into),
+            Some(ToolsCommands::Rename { from, to }) => rename_existing_tool(store, &from, &to),
+        },
+        Commands::Dedupe { threshold } => {
+            show_duplicates(store, threshold);
+        }
+        Commands::ImportHistory { from, file, limit } => {
+            import_history(store, from.map(Into::into), file.as_deref(), limit);
+        }
+        Commands::Tag { command } => match command {
+            TagCommands::Add { id, tags } => add_tags(store, id, &tags),
+            TagCommands::Remove { id, tags } => remove_tags(store, id, &tags),
+        },
+        Commands::Backup { file } => {
+            backup_to_file(&SqliteStore, &file);
+        }
+        Commands::Restore { file, yes } => {
+            restore_from_file(&SqliteStore, &file, yes);
+        }
+        Commands::Doctor { yes } => {
+            run_doctor(&SqliteStore, yes);
+        }
+        Commands::Db { command } => match command {
+            DbCommands::Migrate { status: true } => show_migration_status(&SqliteStore),
+            DbCommands::Migrate { status: false } => apply_migrations(&SqliteStore),
+        },
         Commands::GenerateConfig => {
             generate_config_file();
         }
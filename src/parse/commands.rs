@@ -0,0 +1,104 @@
+use crate::parse::lexer::{TokenKind, substitutions, tokenize};
+
+/// Shell keywords that open or close a compound command rather than run a program.
+const SHELL_KEYWORDS: [&str; 13] = [
+    "if", "then", "else", "elif", "fi", "while", "until", "do", "done", "esac", "{", "}", "!",
+];
+
+/// Keywords whose following words are names or patterns, such as the variable of a `for` loop,
+/// rather than a command.
+const HEADER_KEYWORDS: [&str; 4] = ["for", "case", "select", "function"];
+
+/// One command as the shell runs it: the program and its arguments, with the variable
+/// assignments before it and the files it redirects to or from kept apart.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimpleCommand<'a> {
+    pub assignments: Vec<&'a str>,
+    pub words: Vec<&'a str>,
+    pub redirect_targets: Vec<&'a str>,
+    /// Whether this is the header of a `for` loop, `case` or function, such as `for f in *`,
+    /// rather than a command that runs a program.
+    pub header: bool,
+}
+
+/// Splits a command into every simple command it runs, in order. Pipelines, lists, compound
+/// commands and lines are split apart, and the commands inside command and process
+/// substitutions follow the command they appear in.
+pub fn simple_commands(command: &str) -> Vec<SimpleCommand<'_>> {
+    let mut commands = Vec::new();
+    let mut current = SimpleCommand::default();
+    let mut nested = Vec::new();
+    // Set after a redirection that takes a target, to whether the target is a file rather than
+    // a heredoc delimiter or string.
+    let mut redirect_target: Option<bool> = None;
+    // Whether the words being read are the patterns of a `case` clause, such as `a|b` in
+    // `a|b) cmd ;;`, which run until the `)`.
+    let mut case_patterns = false;
+    for token in tokenize(command) {
+        match token.kind {
+            TokenKind::Word if case_patterns => case_patterns = token.text != "esac",
+            TokenKind::Operator => {
+                // A `(` straight after a word starts a function definition, as in `name() {`.
+                current.header |= token.text == "(" && !current.words.is_empty();
+                finish(&mut commands, &mut current, &mut nested);
+                case_patterns = if case_patterns {
+                    token.text != ")"
+                } else {
+                    token.text == ";;"
+                };
+            }
+            TokenKind::Redirection => {
+                redirect_target = token.expects_target().then(|| !token.text.contains("<<"))
+            }
+            TokenKind::Word | TokenKind::Substitution => {
+                nested.extend(
+                    substitutions(token.text)
+                        .into_iter()
+                        .flat_map(simple_commands),
+                );
+                if let Some(file) = redirect_target.take() {
+                    if file {
+                        current.redirect_targets.push(token.text);
+                    }
+                } else if current.words.is_empty() && is_assignment(token.text) {
+                    current.assignments.push(token.text);
+                } else if current.words.is_empty() && SHELL_KEYWORDS.contains(&token.text) {
+                    continue;
+                } else {
+                    current.header |=
+                        current.words.is_empty() && HEADER_KEYWORDS.contains(&token.text);
+                    case_patterns = current.words.first() == Some(&"case") && token.text == "in";
+                    current.words.push(token.text);
+                }
+            }
+            TokenKind::Comment | TokenKind::Heredoc => {}
+        }
+    }
+    finish(&mut commands, &mut current, &mut nested);
+    commands
+}
+
+/// Ends the current command, dropping it if it is empty, and adds the commands nested inside it
+/// after it.
+fn finish<'a>(
+    commands: &mut Vec<SimpleCommand<'a>>,
+    current: &mut SimpleCommand<'a>,
+    nested: &mut Vec<SimpleCommand<'a>>,
+) {
+    let command = std::mem::take(current);
+    if !(command.words.is_empty()
+        && command.assignments.is_empty()
+        && command.redirect_targets.is_empty())
+    {
+        commands.push(command);
+    }
+    commands.append(nested);
+}
+
+/// Whether a word assigns a variable, such as `FOO=1` or `PATH="$HOME/bin:$PATH"`.
+pub fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
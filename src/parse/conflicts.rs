@@ -0,0 +1,136 @@
+use dialoguer::console::Style;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Input, Select};
+use docu::store::scriptlet_store::ScriptletStore;
+
+/// What to do when a new scriptlet is given a name that is already taken.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ConflictStrategy {
+    /// Don't add the scriptlet.
+    Fail,
+    /// Replace the existing scriptlet, keeping it as a revision.
+    Overwrite,
+    /// Add a numeric suffix to the new name, e.g. `name-2`.
+    Suffix,
+}
+
+pub enum NameChoice {
+    /// Save the scriptlet as a new scriptlet with this name.
+    New(String),
+    /// Replace the scriptlet with this id.
+    Overwrite { id: i64, name: String },
+}
+
+/// Prompts for the name of a new scriptlet, unless one was already given. If the name is taken,
+/// the conflict is resolved with `strategy`, or by asking the user when no strategy was given, in
+/// which case the prompt flags the taken name as soon as it is entered.
+/// When the user can't be asked a taken name fails. Fails with the reason when the scriptlet can't
+/// be added under the name.
+pub fn prompt_name(
+    store: &dyn ScriptletStore,
+    mut given: Option<String>,
+    strategy: Option<ConflictStrategy>,
+    interactive: bool,
+) -> Result<NameChoice, String> {
+    let strategy = strategy.or((!interactive).then_some(ConflictStrategy::Fail));
+    loop {
+        let name = given
+            .take()
+            .unwrap_or_else(|| ask_name(store, strategy.is_none()));
+        match resolve_name(store, name.trim(), strategy)? {
+            Resolution::Resolved(choice) => return Ok(choice),
+            Resolution::Rename => continue,
+        }
+    }
+}
+
+/// Asks for a name. With `flag_taken`, a name that is already taken is rejected the first time
+/// it is entered, saying which scriptlet has it, and accepted if it is entered again so the user
+/// can choose what to do about it.
+fn ask_name(store: &dyn ScriptletStore, flag_taken: bool) -> String {
+    let red = Style::new().red();
+    let mut flagged: Option<String> = None;
+    Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter the name for your scriptlet")
+        .validate_with(|answer: &String| -> Result<(), String> {
+            let name = answer.trim();
+            if name.is_empty() {
+                return Err(red.apply_to("cannot be empty").to_string());
+            }
+            // A failed lookup is reported once the name is resolved.
+            let Ok(Some(id)) = store.find_by_name(name) else {
+                return Ok(());
+            };
+            if !flag_taken || flagged.as_deref() == Some(name) {
+                return Ok(());
+            }
+            flagged = Some(name.to_string());
+            Err(red
+                .apply_to(format!(
+                    "{name} is taken by scriptlet {id}, enter it again to overwrite it or save \
+                     with a suffix"
+                ))
+                .to_string())
+        })
+        .interact_text()
+        .expect("Failed to read input")
+}
+
+enum Resolution {
+    Resolved(NameChoice),
+    Rename,
+}
+
+fn resolve_name(
+    store: &dyn ScriptletStore,
+    name: &str,
+    strategy: Option<ConflictStrategy>,
+) -> Result<Resolution, String> {
+    let Some(id) = store.find_by_name(name).map_err(|e| e.to_string())? else {
+        return Ok(Resolution::Resolved(NameChoice::New(name.to_string())));
+    };
+    let overwrite = NameChoice::Overwrite {
+        id,
+        name: name.to_string(),
+    };
+    Ok(match strategy {
+        Some(ConflictStrategy::Fail) => {
+            return Err(format!("A scriptlet named {name} already exists ({id})"));
+        }
+        Some(ConflictStrategy::Overwrite) => Resolution::Resolved(overwrite),
+        Some(ConflictStrategy::Suffix) => {
+            Resolution::Resolved(NameChoice::New(suffixed_name(store, name)?))
+        }
+        None => {
+            let suffixed = suffixed_name(store, name)?;
+            let options = [
+                format!("Overwrite the existing scriptlet {name} ({id})"),
+                format!("Save as {suffixed}"),
+                "Enter a different name".to_string(),
+            ];
+            let choice = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("A scriptlet named {name} already exists"))
+                .items(&options)
+                .default(2)
+                .interact()
+                .expect("Failed to read selection");
+            match choice {
+                0 => Resolution::Resolved(overwrite),
+                1 => Resolution::Resolved(NameChoice::New(suffixed)),
+                _ => Resolution::Rename,
+            }
+        }
+    })
+}
+
+/// The first of `name-2`, `name-3`, ... that isn't taken yet.
+fn suffixed_name(store: &dyn ScriptletStore, name: &str) -> Result<String, String> {
+    (2..)
+        .map(|n| format!("{name}-{n}"))
+        .find_map(|candidate| match store.find_by_name(&candidate) {
+            Ok(None) => Some(Ok(candidate)),
+            Ok(Some(_)) => None,
+            Err(err) => Some(Err(err.to_string())),
+        })
+        .expect("ran out of suffixes")
+}
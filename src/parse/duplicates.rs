@@ -0,0 +1,73 @@
+use crate::parse::parser::get_input;
+use crate::parse::similarity::{DUPLICATE_THRESHOLD, find_similar};
+use crate::parse::tools::extract_tools;
+use crate::tui::print::show_similar_scriptlets_tui;
+use dialoguer::Select;
+use dialoguer::theme::ColorfulTheme;
+use docu::database::data_types::{NewScriptlet, ScriptletData, ScriptletFilter};
+use docu::store::scriptlet_store::ScriptletStore;
+
+pub enum DuplicateResolution {
+    /// Carry on adding the new scriptlet.
+    Add,
+    /// Don't add the new scriptlet.
+    Skip,
+}
+
+/// Warns about existing scriptlets with nearly the same command, and lets the user decide
+/// whether to add the new one anyway, skip it, or fold its description into an existing one.
+/// When the user can't be asked the new one is added anyway.
+pub fn resolve_duplicates(
+    store: &dyn ScriptletStore,
+    command: &str,
+    interactive: bool,
+) -> Result<DuplicateResolution, String> {
+    let scriptlets = store
+        .list(&ScriptletFilter::default())
+        .map_err(|e| e.to_string())?;
+    let similar = find_similar(command, &scriptlets, DUPLICATE_THRESHOLD);
+    if similar.is_empty() {
+        return Ok(DuplicateResolution::Add);
+    }
+    println!("This scriptlet looks like one you have already saved:");
+    show_similar_scriptlets_tui(&similar);
+    if !interactive {
+        println!("Adding it anyway");
+        return Ok(DuplicateResolution::Add);
+    }
+
+    let mut options = vec!["Add anyway".to_string(), "Skip".to_string()];
+    options.extend(
+        similar
+            .iter()
+            .map(|(s, _)| format!("Merge description into {} ({})", s.name, s.id)),
+    );
+    let choice = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("What would you like to do?")
+        .items(&options)
+        .default(0)
+        .interact()
+        .expect("Failed to read selection");
+    Ok(match choice {
+        0 => DuplicateResolution::Add,
+        1 => DuplicateResolution::Skip,
+        n => {
+            merge_description(store, similar[n - 2].0)?;
+            DuplicateResolution::Skip
+        }
+    })
+}
+
+fn merge_description(store: &dyn ScriptletStore, existing: &ScriptletData) -> Result<(), String> {
+    let addition = get_input(&format!(
+        "Enter the description to add to {}",
+        existing.name
+    ));
+    let description = format!("{}\n{}", existing.description, addition.trim());
+    let scriptlet = NewScriptlet::new(&existing.name, &existing.command)
+        .description(description)
+        .tools(extract_tools(&existing.command));
+    store
+        .update(existing.id, &scriptlet)
+        .map_err(|e| e.to_string())
+}
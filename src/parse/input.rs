@@ -0,0 +1,24 @@
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::path::Path;
+
+/// Reads the command to add. It is taken from the argument if there is one, otherwise from the
+/// file given with `--file` (`-` meaning stdin), and otherwise from stdin if something is piped
+/// into docu. Returns `None` if there is nowhere to read a command from.
+pub fn read_command(args: Option<String>, file: Option<&Path>) -> io::Result<Option<String>> {
+    if args.is_some() {
+        return Ok(args);
+    }
+    match file {
+        Some(path) if path == Path::new("-") => read_stdin().map(Some),
+        Some(path) => fs::read_to_string(path).map(Some),
+        None if !io::stdin().is_terminal() => read_stdin().map(Some),
+        None => Ok(None),
+    }
+}
+
+fn read_stdin() -> io::Result<String> {
+    let mut command = String::new();
+    io::stdin().read_to_string(&mut command)?;
+    Ok(command)
+}
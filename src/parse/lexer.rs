@@ -0,0 +1,393 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A command name or argument. Quoted and unquoted fragments that touch, as in
+    /// `--opt="a b"`, make up a single word.
+    Word,
+    /// A word that is nothing but a single substitution or expansion, such as `$(date)`,
+    /// `` `date` ``, `${name}`, `$HOME` or `<(sort a)`.
+    Substitution,
+    /// A redirection operator with its file descriptors, such as `>`, `2>&1` or `<<-`. The file
+    /// being redirected to or from is the word that follows it.
+    Redirection,
+    /// An operator that separates commands: `|`, `|&`, `||`, `&&`, `;`, `;;`, `&`, `(`, `)` or a
+    /// newline.
+    Operator,
+    /// A comment, from `#` to the end of the line.
+    Comment,
+    /// The body of a heredoc, up to and including the line that ends it.
+    Heredoc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// The byte range of the token in the command.
+    pub span: Range<usize>,
+}
+
+impl Token<'_> {
+    /// Whether this is a redirection followed by the file it uses or, for a heredoc, its
+    /// delimiter. Redirections such as `2>&1` and `>&-` name their target themselves.
+    pub fn expects_target(&self) -> bool {
+        self.kind == TokenKind::Redirection
+            && !(self.text.contains(['<', '>'])
+                && self.text.contains('&')
+                && self
+                    .text
+                    .ends_with(|c: char| c.is_ascii_digit() || c == '-'))
+    }
+}
+
+/// Splits a command into tokens following the POSIX shell grammar, plus the bash extensions
+/// people commonly use such as `&>`, `<<<`, `$'...'` and process substitution. Whitespace and
+/// escaped newlines aren't tokens. Unterminated quotes and substitutions run to the end of the
+/// command rather than failing.
+pub fn tokenize(command: &str) -> Vec<Token<'_>> {
+    let mut lexer = Lexer::new(command);
+    lexer.run();
+    lexer.tokens
+}
+
+/// The commands run by the command and process substitutions in a word, such as `date` in
+/// `backup-$(date +%F).tar`. Arithmetic expansions and anything in single quotes are skipped.
+pub fn substitutions(word: &str) -> Vec<&str> {
+    let mut lexer = Lexer::new(word);
+    let mut commands = Vec::new();
+    let mut double_quoted = false;
+    while let Some(&byte) = lexer.bytes.get(lexer.pos) {
+        match (byte, lexer.peek(1)) {
+            (b'\\', _) => lexer.advance(2),
+            (b'\'', _) if !double_quoted => lexer.skip_single_quote(),
+            (b'"', _) => {
+                double_quoted = !double_quoted;
+                lexer.advance(1);
+            }
+            (b'$', Some(b'(')) if lexer.peek(2) == Some(b'(') => {
+                lexer.advance(1);
+                lexer.skip_group(b'(', b')');
+            }
+            (b'$' | b'<' | b'>', Some(b'(')) => {
+                lexer.advance(1);
+                let start = lexer.pos + 1;
+                let closed = lexer.skip_group(b'(', b')');
+                commands.push(&word[start..lexer.pos - usize::from(closed)]);
+            }
+            (b'`', _) => {
+                let start = lexer.pos + 1;
+                lexer.skip_backticks();
+                let closed = word[start..lexer.pos].ends_with('`');
+                commands.push(&word[start..lexer.pos - usize::from(closed)]);
+            }
+            _ => lexer.advance(1),
+        }
+    }
+    commands
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    tokens: Vec<Token<'a>>,
+    /// Set after `<<` or `<<-`, whose delimiter is the next word. Holds whether leading tabs are
+    /// stripped from the body.
+    heredoc_operator: Option<bool>,
+    /// The delimiters of heredocs started on the current line, whose bodies follow it.
+    heredocs: Vec<(String, bool)>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            bytes: source.as_bytes(),
+            pos: 0,
+            tokens: Vec::new(),
+            heredoc_operator: None,
+            heredocs: Vec::new(),
+        }
+    }
+
+    fn run(&mut self) {
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            match byte {
+                b' ' | b'\t' | b'\r' => self.advance(1),
+                b'\\' if self.peek(1) == Some(b'\n') => self.advance(2),
+                b'\n' => {
+                    self.push(TokenKind::Operator, self.pos + 1);
+                    self.read_heredocs();
+                }
+                b'#' => self.push(TokenKind::Comment, self.line_end(self.pos)),
+                _ => {
+                    if let Some(len) = self.redirection_len() {
+                        let operator = &self.bytes[self.pos..self.pos + len];
+                        let strip_tabs = operator.ends_with(b"<<-");
+                        let heredoc =
+                            strip_tabs || operator.ends_with(b"<<") && !operator.ends_with(b"<<<");
+                        self.push(TokenKind::Redirection, self.pos + len);
+                        self.heredoc_operator = heredoc.then_some(strip_tabs);
+                    } else if let Some(len) = self.operator_len() {
+                        self.push(TokenKind::Operator, self.pos + len);
+                    } else {
+                        self.word();
+                    }
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, kind: TokenKind, end: usize) {
+        let text = &self.source[self.pos..end];
+        if let Some(strip_tabs) = self.heredoc_operator.take()
+            && matches!(kind, TokenKind::Word | TokenKind::Substitution)
+        {
+            self.heredocs.push((unquote(text), strip_tabs));
+        }
+        self.tokens.push(Token {
+            kind,
+            text,
+            span: self.pos..end,
+        });
+        self.pos = end;
+    }
+
+    fn advance(&mut self, len: usize) {
+        self.pos = (self.pos + len).min(self.bytes.len());
+    }
+
+    fn peek(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.pos + offset).copied()
+    }
+
+    fn line_end(&self, from: usize) -> usize {
+        self.source[from..]
+            .find('\n')
+            .map_or(self.source.len(), |i| from + i)
+    }
+
+    /// Reads the bodies of the heredocs started on the line that just ended. The newline after
+    /// the last one is left to be read as an operator.
+    fn read_heredocs(&mut self) {
+        let heredocs = std::mem::take(&mut self.heredocs);
+        let count = heredocs.len();
+        for (i, (delimiter, strip_tabs)) in heredocs.into_iter().enumerate() {
+            let mut end = self.pos;
+            loop {
+                let line_end = self.line_end(end);
+                let line = self.source[end..line_end].trim_end_matches('\r');
+                let line = if strip_tabs {
+                    line.trim_start_matches('\t')
+                } else {
+                    line
+                };
+                end = line_end;
+                if line == delimiter || line_end == self.source.len() {
+                    break;
+                }
+                end += 1;
+            }
+            self.push(TokenKind::Heredoc, end);
+            if i + 1 < count && self.peek(0) == Some(b'\n') {
+                self.advance(1);
+            }
+        }
+    }
+
+    fn redirection_len(&self) -> Option<usize> {
+        let rest = &self.bytes[self.pos..];
+        let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+        let operator = &rest[digits..];
+        if digits == 0 && operator.starts_with(b"&>") {
+            return Some(if operator.starts_with(b"&>>") { 3 } else { 2 });
+        }
+        let len = if operator.starts_with(b"<<<") || operator.starts_with(b"<<-") {
+            3
+        } else if [b"<<", b">>", b"<&", b">&", b"<>", b">|"]
+            .iter()
+            .any(|op| operator.starts_with(*op))
+        {
+            2
+        } else if matches!(operator.first(), Some(b'<' | b'>')) && operator.get(1) != Some(&b'(') {
+            1
+        } else {
+            return None;
+        };
+        let mut len = digits + len;
+        if operator.starts_with(b"<&") || operator.starts_with(b">&") {
+            len += rest[len..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit() || **b == b'-')
+                .count();
+        }
+        Some(len)
+    }
+
+    fn operator_len(&self) -> Option<usize> {
+        let rest = &self.bytes[self.pos..];
+        if [b"||", b"|&", b"&&", b";;"]
+            .iter()
+            .any(|op| rest.starts_with(*op))
+        {
+            Some(2)
+        } else if matches!(rest.first(), Some(b'|' | b'&' | b';' | b'(' | b')')) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// Reads a word up to the next unquoted whitespace or operator. It is a substitution if it
+    /// consists of exactly one substitution or expansion.
+    fn word(&mut self) {
+        let start = self.pos;
+        let mut parts = 0;
+        let mut substitution = false;
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            substitution = match (byte, self.peek(1)) {
+                (b' ' | b'\t' | b'\r' | b'\n' | b'|' | b'&' | b';' | b'(' | b')', _) => break,
+                (b'<' | b'>' | b'$', Some(b'(')) => {
+                    self.advance(1);
+                    self.skip_group(b'(', b')');
+                    true
+                }
+                (b'<' | b'>', _) => break,
+                (b'$', Some(b'{')) => {
+                    self.advance(1);
+                    self.skip_group(b'{', b'}');
+                    true
+                }
+                (b'$', Some(next)) if next.is_ascii_digit() || b"@*#?$!-".contains(&next) => {
+                    self.advance(2);
+                    true
+                }
+                (b'$', Some(next)) if next.is_ascii_alphabetic() || next == b'_' => {
+                    self.advance(1);
+                    while self
+                        .peek(0)
+                        .is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_')
+                    {
+                        self.advance(1);
+                    }
+                    true
+                }
+                (b'$', Some(b'\'')) => {
+                    self.advance(1);
+                    self.skip_ansi_quote();
+                    false
+                }
+                (b'`', _) => {
+                    self.skip_backticks();
+                    true
+                }
+                (b'\\', _) => {
+                    self.advance(2);
+                    false
+                }
+                (b'\'', _) => {
+                    self.skip_single_quote();
+                    false
+                }
+                (b'"', _) => {
+                    self.skip_double_quote();
+                    false
+                }
+                _ => {
+                    self.advance(1);
+                    false
+                }
+            };
+            parts += 1;
+        }
+        let kind = if parts == 1 && substitution {
+            TokenKind::Substitution
+        } else {
+            TokenKind::Word
+        };
+        let end = self.pos;
+        self.pos = start;
+        self.push(kind, end);
+    }
+
+    /// Moves past a bracketed group such as the body of `$(...)`, `$((...))`, `${...}` or
+    /// `<(...)`, including nested groups and any quoted brackets inside it.
+    fn skip_group(&mut self, open: u8, close: u8) -> bool {
+        let mut depth = 0;
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            match byte {
+                b'\\' => self.advance(2),
+                b'\'' => self.skip_single_quote(),
+                b'"' => self.skip_double_quote(),
+                b'`' => self.skip_backticks(),
+                _ => {
+                    self.advance(1);
+                    if byte == open {
+                        depth += 1;
+                    } else if byte == close {
+                        depth -= 1;
+                        if depth == 0 {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn skip_single_quote(&mut self) {
+        self.advance(1);
+        match self.source[self.pos..].find('\'') {
+            Some(i) => self.advance(i + 1),
+            None => self.pos = self.bytes.len(),
+        }
+    }
+
+    /// Moves past `'...'` in `$'...'`, where backslash escapes are allowed.
+    fn skip_ansi_quote(&mut self) {
+        self.advance(1);
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            match byte {
+                b'\\' => self.advance(2),
+                b'\'' => return self.advance(1),
+                _ => self.advance(1),
+            }
+        }
+    }
+
+    fn skip_double_quote(&mut self) {
+        self.advance(1);
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            match (byte, self.peek(1)) {
+                (b'\\', _) => self.advance(2),
+                (b'"', _) => return self.advance(1),
+                (b'$', Some(b'(')) => {
+                    self.advance(1);
+                    self.skip_group(b'(', b')');
+                }
+                (b'`', _) => self.skip_backticks(),
+                _ => self.advance(1),
+            }
+        }
+    }
+
+    fn skip_backticks(&mut self) {
+        self.advance(1);
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            match byte {
+                b'\\' => self.advance(2),
+                b'`' => return self.advance(1),
+                _ => self.advance(1),
+            }
+        }
+    }
+}
+
+/// The heredoc delimiter a word stands for, which is the word with its quotes removed.
+fn unquote(word: &str) -> String {
+    word.chars()
+        .filter(|c| !matches!(c, '\'' | '"' | '\\'))
+        .collect()
+}
@@ -1 +1,10 @@
+pub mod commands;
+pub mod conflicts;
+pub mod duplicates;
+pub mod input;
+pub mod lexer;
 pub mod parser;
+pub mod placeholders;
+pub mod review;
+pub mod similarity;
+pub mod tools;
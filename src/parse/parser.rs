@@ -1,28 +1,150 @@
-use crate::database::connect::add_scriptlet;
+use crate::parse::conflicts::{ConflictStrategy, NameChoice, prompt_name};
+use crate::parse::duplicates::{DuplicateResolution, resolve_duplicates};
+use crate::parse::placeholders::{apply_placeholders, detect_placeholders};
+use crate::parse::review::{accept_placeholders, review_placeholders};
+use crate::parse::tools::extract_tools;
 use dialoguer::Input;
-use dialoguer::console::Style;
+use dialoguer::console::{Style, Term};
 use dialoguer::theme::ColorfulTheme;
-use regex::Regex;
+use docu::database::data_types::NewScriptlet;
+use docu::store::scriptlet_store::ScriptletStore;
+use std::time::SystemTime;
+use std::{env, fs};
 
-pub fn parse_scriptlet(scriptlet_string: &str) {
-    let scriptlet = arg_regex()
-        .find_iter(scriptlet_string)
-        .map(|m| m.as_str().to_string())
+/// The parts of a new scriptlet given up front, e.g. on the command line, which aren't asked for.
+#[derive(Default)]
+pub struct AddOptions {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Replaces the tools detected in the command, unless empty.
+    pub tools: Vec<String>,
+    pub tags: Vec<String>,
+    /// Keep the command exactly as given, without replacing any values with placeholders.
+    pub no_placeholders: bool,
+    pub on_conflict: Option<ConflictStrategy>,
+    pub source_url: Option<String>,
+    /// Where and when the command was run, e.g. as recorded in a history. The current directory
+    /// and host are used for whatever isn't given.
+    pub cwd: Option<String>,
+    pub host: Option<String>,
+    pub created_at: Option<SystemTime>,
+}
+
+/// Adds a scriptlet, asking for whatever `options` doesn't give. When both the name and the
+/// description are given nothing is asked at all: the detected placeholders are kept as
+/// proposed, and a possible duplicate is added anyway. Fails if something has to be asked but
+/// there is no terminal to ask on, if the name is taken and mustn't be replaced, or if the store
+/// can't be read or written.
+pub fn parse_scriptlet(
+    store: &dyn ScriptletStore,
+    scriptlet_string: &str,
+    options: AddOptions,
+) -> Result<(), String> {
+    let ask = options.name.is_none() || options.description.is_none();
+    if ask && !Term::stderr().is_term() {
+        return Err(
+            "There is no terminal to ask for the name and description of the scriptlet, pass \
+             them with --name and --description"
+                .to_string(),
+        );
+    }
+    let command = normalize_command_text(scriptlet_string);
+    let detected = apply_placeholders(&command, &detect_placeholders(&command));
+    if let DuplicateResolution::Skip = resolve_duplicates(store, &detected, ask)? {
+        return Ok(());
+    }
+    let (command, placeholders) = if options.no_placeholders {
+        (command, Vec::new())
+    } else if ask {
+        review_placeholders(&command)
+    } else {
+        accept_placeholders(&command)
+    };
+    let name = prompt_name(store, options.name, options.on_conflict, ask)?;
+    let tools = if options.tools.is_empty() {
+        extract_tools(&command)
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    } else {
+        options.tools
+    };
+    let description = options
+        .description
+        .unwrap_or_else(|| get_input("Enter the description for your scriptlet"));
+    let tags = if options.tags.is_empty() && ask {
+        vec![get_optional_input(
+            "Enter tags for your scriptlet (optional, space separated)",
+        )]
+    } else {
+        options.tags
+    };
+    let tags = normalize_tags(&tags);
+    let (name, overwrite) = match name {
+        NameChoice::New(name) => (name, None),
+        NameChoice::Overwrite { id, name } => (name, Some(id)),
+    };
+    let mut scriptlet = NewScriptlet::new(name, &command)
+        .description(description)
+        .tools(tools)
+        .tags(tags)
+        .placeholders(placeholders);
+    scriptlet.source_url = options.source_url;
+    scriptlet.cwd = options.cwd.or_else(|| {
+        env::current_dir()
+            .ok()
+            .map(|dir| dir.to_string_lossy().into_owned())
+    });
+    scriptlet.host = options.host.or_else(hostname);
+    scriptlet.created_at = options.created_at;
+    match overwrite {
+        None => store.add(&scriptlet).map(|_| ()),
+        Some(id) => store.update(id, &scriptlet),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// The name of this machine, read the same way `hostname` does on Linux, falling back to the
+/// variables shells and Windows set.
+fn hostname() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .or_else(|_| fs::read_to_string("/etc/hostname"))
+        .ok()
+        .or_else(|| env::var("HOSTNAME").ok())
+        .or_else(|| env::var("COMPUTERNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Unifies line endings and trims the whitespace around the command, while keeping the newlines
+/// and indentation inside it.
+fn normalize_command_text(command: &str) -> String {
+    command.replace("\r\n", "\n").trim().to_string()
+}
+
+/// Splits raw tag input on whitespace and commas, dropping any leading `#` and duplicates, so
+/// `#k8s, Networking` and `k8s networking` produce the same tags.
+pub fn normalize_tags<S: AsRef<str>>(raw: &[S]) -> Vec<String> {
+    let mut tags: Vec<String> = raw
+        .iter()
+        .flat_map(|s| s.as_ref().split([',', ' ', '\t']))
+        .map(|tag| tag.trim().trim_start_matches('#').to_lowercase())
+        .filter(|tag| !tag.is_empty())
         .collect();
-    let scriptlet = replace_variables(scriptlet);
-    let name = get_input("Enter the name for your scriptlet");
-    let tools = scriptlet_string
-        .split('|')
-        .map(str::trim)
-        .filter_map(|s| s.split(' ').next())
-        .collect::<Vec<&str>>();
-    let command = scriptlet.join(" ");
-    let description = get_input("Enter the description for your scriptlet");
-    add_scriptlet(&name, tools, &command, &description)
-        .expect("Scriptlet could not be added to database.");
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+pub fn get_optional_input(description: &str) -> String {
+    Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(description)
+        .allow_empty(true)
+        .interact_text()
+        .expect("Failed to read input")
 }
 
-fn get_input(description: &str) -> String {
+pub fn get_input(description: &str) -> String {
     Input::with_theme(&ColorfulTheme::default())
         .with_prompt(description)
         .validate_with(|user_answer: &String| {
@@ -36,23 +158,3 @@ fn get_input(description: &str) -> String {
         .interact_text()
         .expect("Failed to read input")
 }
-
-fn replace_variables(scriptlet: Vec<String>) -> Vec<String> {
-    scriptlet.into_iter().map(replace_file).collect()
-}
-
-fn replace_file(arg: String) -> String {
-    if file_regex().is_match(&arg) {
-        "$FILE".into()
-    } else {
-        arg
-    }
-}
-
-fn file_regex() -> Regex {
-    Regex::new(r".*\.[A-Za-z0-9]+$").expect("Regex could not be compiled.")
-}
-
-fn arg_regex() -> Regex {
-    Regex::new(r#""[^"]*"|'[^']*'|\S+"#).expect("Regex could not be compiled.")
-}
@@ -0,0 +1,587 @@
+use crate::parse::commands::simple_commands;
+use crate::parse::tools::{program_name, unwrap_command};
+use docu::database::data_types::PlaceholderType;
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// The kinds of value that are replaced with a placeholder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlaceholderKind {
+    File,
+    Dir,
+    Glob,
+    Url,
+    Host,
+    Ip,
+    Port,
+    Email,
+    Sha,
+    GitRef,
+    Number,
+    /// A value that no detector recognised but was picked by hand.
+    Argument,
+}
+
+impl PlaceholderKind {
+    /// The variable name used for placeholders of this kind, before any number is added.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlaceholderKind::File => "FILE",
+            PlaceholderKind::Dir => "DIR",
+            PlaceholderKind::Glob => "GLOB",
+            PlaceholderKind::Url => "URL",
+            PlaceholderKind::Host => "HOST",
+            PlaceholderKind::Ip => "IP",
+            PlaceholderKind::Port => "PORT",
+            PlaceholderKind::Email => "EMAIL",
+            PlaceholderKind::Sha => "SHA",
+            PlaceholderKind::GitRef => "REF",
+            PlaceholderKind::Number => "NUM",
+            PlaceholderKind::Argument => "ARG",
+        }
+    }
+
+    /// The type of value a placeholder of this kind replacing `value` takes. Numbers that don't
+    /// fit an integer, such as `1.5` or `1e3`, are text.
+    pub fn value_type(&self, value: &str) -> PlaceholderType {
+        match self {
+            PlaceholderKind::File | PlaceholderKind::Dir | PlaceholderKind::Glob => {
+                PlaceholderType::Path
+            }
+            PlaceholderKind::Port | PlaceholderKind::Number if value.parse::<i64>().is_ok() => {
+                PlaceholderType::Int
+            }
+            _ => PlaceholderType::Text,
+        }
+    }
+}
+
+/// Where a word appears, for detectors that depend on more than the word itself.
+pub struct WordContext<'a> {
+    /// The program the word is an argument of, without its directory, looking through wrappers
+    /// such as `sudo`.
+    pub program: &'a str,
+    /// The first argument that isn't an option, such as `checkout` in `git checkout main`, if it
+    /// comes before the word.
+    pub subcommand: Option<&'a str>,
+    /// The option the word may be the value of: the word before it if that is an option, or the
+    /// part before the `=` in `--port=8080`.
+    pub option: Option<&'a str>,
+    /// Whether the word is the first argument that is neither an option nor the value of one,
+    /// such as `host` in `ssh -p 2222 host uptime`.
+    pub first_argument: bool,
+}
+
+/// Recognises one kind of value that should become a placeholder.
+pub trait Detector {
+    fn kind(&self) -> PlaceholderKind;
+
+    /// Whether the value, with any surrounding quotes removed, is of this kind.
+    fn detect(&self, value: &str, context: &WordContext) -> bool;
+}
+
+/// The detectors to try, in order. The first one that recognises a value decides its kind.
+fn detectors() -> Vec<Box<dyn Detector>> {
+    vec![
+        Box::new(UrlDetector),
+        Box::new(EmailDetector),
+        Box::new(IpDetector),
+        Box::new(PortDetector),
+        Box::new(GlobDetector),
+        Box::new(ShaDetector),
+        Box::new(HostDetector),
+        Box::new(DirDetector),
+        Box::new(FileDetector),
+        Box::new(GitRefDetector),
+        Box::new(NumberDetector),
+    ]
+}
+
+/// A value in a command that was recognised as something that changes between uses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placeholder {
+    pub kind: PlaceholderKind,
+    /// The variable name that replaces the value, without the `$`.
+    pub name: String,
+    /// The value as it was written, without quotes.
+    pub value: String,
+    /// The bytes of the command that are replaced, including any quotes.
+    pub span: Range<usize>,
+    pub quoted: bool,
+}
+
+/// A word in a command that could be replaced with a placeholder: an argument, the value of a
+/// `--option=value`, or the target of a redirection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate<'a> {
+    /// The kind of value the detectors recognised, if any.
+    pub kind: Option<PlaceholderKind>,
+    /// The value as it was written, without quotes.
+    pub value: &'a str,
+    /// The bytes of the command that are replaced, including any quotes.
+    pub span: Range<usize>,
+    pub quoted: bool,
+}
+
+/// Finds the values in a command that should become placeholders, in the order they appear.
+/// Every kind gets its own name, numbered if a command has more than one value of that kind,
+/// e.g. `$FILE1` and `$FILE2`. Values that are repeated get the same name each time.
+pub fn detect_placeholders(command: &str) -> Vec<Placeholder> {
+    name_placeholders(
+        candidates(command)
+            .into_iter()
+            .filter(|candidate| candidate.kind.is_some()),
+    )
+}
+
+/// Every word of a command the detectors look at, in the order they appear, whether or not it
+/// was recognised. Options, and words that expand variables or are only partly quoted, are left
+/// out.
+pub fn candidates(command: &str) -> Vec<Candidate<'_>> {
+    let detectors = detectors();
+    let mut found = Vec::new();
+    let mut detect = |word, context: &WordContext| {
+        if let Some(candidate) = detect_word(&detectors, command, word, context) {
+            found.push(candidate);
+        }
+    };
+    for simple in simple_commands(command) {
+        let words = unwrap_command(&simple.words);
+        let program = words.first().map_or("", |program| program_name(program));
+        let args = words.get(1..).unwrap_or_default();
+        let option_value =
+            |i: usize| i > 0 && args[i - 1].starts_with('-') && !args[i - 1].contains('=');
+        let first_argument =
+            (0..args.len()).find(|&i| !args[i].starts_with('-') && !option_value(i));
+        for (i, word) in args.iter().enumerate() {
+            let previous = i.checked_sub(1).map(|i| args[i]);
+            let mut context = WordContext {
+                program,
+                subcommand: args[..i].iter().find(|arg| !arg.starts_with('-')).copied(),
+                option: previous.filter(|arg| arg.starts_with('-')),
+                first_argument: first_argument == Some(i),
+            };
+            match word.split_once('=') {
+                Some((option, value)) if option.starts_with("--") => {
+                    context.option = Some(option);
+                    detect(value, &context);
+                }
+                _ if word.starts_with('-') => {}
+                _ => detect(word, &context),
+            }
+        }
+        let context = WordContext {
+            program,
+            subcommand: None,
+            option: None,
+            first_argument: false,
+        };
+        for target in &simple.redirect_targets {
+            detect(target, &context);
+        }
+    }
+    found.sort_by_key(|candidate| candidate.span.start);
+    found
+}
+
+/// Names the chosen candidates after their kinds, treating any without a kind as
+/// `PlaceholderKind::Argument`. Kinds with more than one distinct value are numbered.
+pub fn name_placeholders<'a>(chosen: impl IntoIterator<Item = Candidate<'a>>) -> Vec<Placeholder> {
+    let chosen: Vec<Candidate> = chosen.into_iter().collect();
+    let kind_of = |candidate: &Candidate| candidate.kind.unwrap_or(PlaceholderKind::Argument);
+    let mut values: HashMap<PlaceholderKind, Vec<&str>> = HashMap::new();
+    for candidate in &chosen {
+        let values = values.entry(kind_of(candidate)).or_default();
+        if !values.contains(&candidate.value) {
+            values.push(candidate.value);
+        }
+    }
+    chosen
+        .iter()
+        .map(|candidate| {
+            let kind = kind_of(candidate);
+            let values = &values[&kind];
+            let name = match values.iter().position(|v| *v == candidate.value) {
+                Some(i) if values.len() > 1 => format!("{}{}", kind.name(), i + 1),
+                _ => kind.name().to_string(),
+            };
+            Placeholder {
+                kind,
+                name,
+                value: candidate.value.to_string(),
+                span: candidate.span.clone(),
+                quoted: candidate.quoted,
+            }
+        })
+        .collect()
+}
+
+/// Replaces each placeholder's value with its variable. Quoted values are replaced with a double
+/// quoted variable, so the shell still expands it.
+pub fn apply_placeholders(command: &str, placeholders: &[Placeholder]) -> String {
+    let mut replaced = String::with_capacity(command.len());
+    let mut copied = 0;
+    for placeholder in placeholders {
+        replaced.push_str(&command[copied..placeholder.span.start]);
+        if placeholder.quoted {
+            replaced.push_str(&format!("\"${}\"", placeholder.name));
+        } else {
+            replaced.push_str(&format!("${}", placeholder.name));
+        }
+        copied = placeholder.span.end;
+    }
+    replaced.push_str(&command[copied..]);
+    replaced
+}
+
+/// A word, which is a slice of the command, as a candidate, along with the kind of the first
+/// detector that recognises it.
+fn detect_word<'a>(
+    detectors: &[Box<dyn Detector>],
+    command: &str,
+    word: &'a str,
+    context: &WordContext,
+) -> Option<Candidate<'a>> {
+    let (value, quoted) = unquote(word)?;
+    let start = word.as_ptr() as usize - command.as_ptr() as usize;
+    Some(Candidate {
+        kind: detectors
+            .iter()
+            .find(|detector| detector.detect(value, context))
+            .map(|detector| detector.kind()),
+        value,
+        span: start..start + word.len(),
+        quoted,
+    })
+}
+
+/// The value of a word without the quotes around it, and whether it was quoted. Words that
+/// expand variables or are only partly quoted are left alone.
+fn unquote(word: &str) -> Option<(&str, bool)> {
+    let quoted = ['"', '\'']
+        .iter()
+        .find(|quote| word.len() >= 2 && word.starts_with(**quote) && word.ends_with(**quote));
+    let (value, quoted) = match quoted {
+        Some(_) => (&word[1..word.len() - 1], true),
+        None => (word, false),
+    };
+    let plain = !value.is_empty() && !value.contains(['"', '\'', '\\', '$', '`', ' ']);
+    plain.then_some((value, quoted))
+}
+
+static URL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([A-Za-z][A-Za-z0-9+.-]*://\S+|[\w.-]+@[\w.-]+:\S*)$")
+        .expect("Regex could not be compiled.")
+});
+
+static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[\w.+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}$")
+        .expect("Regex could not be compiled.")
+});
+
+static DOMAIN_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([A-Za-z0-9]([A-Za-z0-9-]*[A-Za-z0-9])?\.)+([A-Za-z]{2,})(:\d{1,5})?$")
+        .expect("Regex could not be compiled.")
+});
+
+static HOST_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([\w.-]+@)?[A-Za-z0-9][A-Za-z0-9.-]*(:\d{1,5})?$")
+        .expect("Regex could not be compiled.")
+});
+
+static PATH_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[\w./~+@%,:-]+$").expect("Regex could not be compiled."));
+
+static GIT_REF_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[A-Za-z0-9_][A-Za-z0-9._/-]*([~^]\d*)*$").expect("Regex could not be compiled.")
+});
+
+/// The top level domains hosts are recognised by. Ones that are also common file extensions,
+/// such as `.sh` and `.rs`, are left out so files aren't mistaken for hosts.
+const TOP_LEVEL_DOMAINS: [&str; 30] = [
+    "com", "org", "net", "io", "dev", "app", "co", "ai", "me", "info", "biz", "edu", "gov",
+    "cloud", "tech", "xyz", "site", "online", "local", "internal", "lan", "test", "example", "uk",
+    "de", "fr", "nl", "eu", "us", "ca",
+];
+
+/// Programs whose arguments are usually hosts, even without a domain.
+const HOST_PROGRAMS: [&str; 11] = [
+    "ssh",
+    "scp",
+    "sftp",
+    "mosh",
+    "ping",
+    "dig",
+    "nslookup",
+    "host",
+    "telnet",
+    "nc",
+    "traceroute",
+];
+
+const PORT_OPTIONS: [&str; 4] = ["-p", "--port", "-P", "--publish"];
+
+const DIR_PROGRAMS: [&str; 4] = ["cd", "mkdir", "rmdir", "pushd"];
+
+const DIR_OPTIONS: [&str; 4] = ["-C", "--directory", "--workdir", "--cwd"];
+
+/// Git options whose value is a branch or other ref, such as `-b` in `git checkout -b topic`.
+const GIT_REF_OPTIONS: [&str; 5] = ["-b", "-B", "-c", "-C", "--onto"];
+
+/// Git subcommands that take branches, tags or other refs.
+const GIT_REF_SUBCOMMANDS: [&str; 13] = [
+    "checkout",
+    "switch",
+    "merge",
+    "rebase",
+    "branch",
+    "cherry-pick",
+    "reset",
+    "diff",
+    "log",
+    "show",
+    "tag",
+    "revert",
+    "push",
+];
+
+struct UrlDetector;
+
+impl Detector for UrlDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::Url
+    }
+
+    fn detect(&self, value: &str, _context: &WordContext) -> bool {
+        URL_REGEX.is_match(value)
+    }
+}
+
+struct EmailDetector;
+
+impl Detector for EmailDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::Email
+    }
+
+    fn detect(&self, value: &str, context: &WordContext) -> bool {
+        EMAIL_REGEX.is_match(value) && !HOST_PROGRAMS.contains(&context.program)
+    }
+}
+
+struct IpDetector;
+
+impl Detector for IpDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::Ip
+    }
+
+    fn detect(&self, value: &str, _context: &WordContext) -> bool {
+        let address = value.split(['/', ':']).next().unwrap_or(value);
+        let octets: Vec<&str> = address.split('.').collect();
+        let ipv4 = octets.len() == 4
+            && octets
+                .iter()
+                .all(|octet| !octet.is_empty() && octet.len() <= 3 && octet.parse::<u8>().is_ok());
+        let ipv6 = value.matches(':').count() >= 2
+            && value.chars().any(|c| c.is_ascii_hexdigit())
+            && value.chars().all(|c| c.is_ascii_hexdigit() || c == ':');
+        ipv4 || ipv6
+    }
+}
+
+struct PortDetector;
+
+impl Detector for PortDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::Port
+    }
+
+    fn detect(&self, value: &str, context: &WordContext) -> bool {
+        context
+            .option
+            .is_some_and(|option| PORT_OPTIONS.contains(&option))
+            && value
+                .split(':')
+                .all(|port| !port.is_empty() && port.parse::<u16>().is_ok())
+    }
+}
+
+struct GlobDetector;
+
+impl Detector for GlobDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::Glob
+    }
+
+    fn detect(&self, value: &str, _context: &WordContext) -> bool {
+        value
+            .chars()
+            .all(|c| c.is_alphanumeric() || "./_-*?[]{},~".contains(c))
+            && value.split('/').any(|segment| segment.starts_with('*'))
+    }
+}
+
+struct ShaDetector;
+
+impl Detector for ShaDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::Sha
+    }
+
+    fn detect(&self, value: &str, _context: &WordContext) -> bool {
+        (7..=40).contains(&value.len())
+            && value.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+            && value.chars().any(|c| c.is_ascii_digit())
+            && value.chars().any(|c| c.is_ascii_alphabetic())
+    }
+}
+
+struct HostDetector;
+
+impl Detector for HostDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::Host
+    }
+
+    fn detect(&self, value: &str, context: &WordContext) -> bool {
+        let domain = DOMAIN_REGEX
+            .captures(value)
+            .is_some_and(|caps| TOP_LEVEL_DOMAINS.contains(&caps[3].to_lowercase().as_str()));
+        // Only the first argument is the host, the ones after it are usually a remote command.
+        let host_argument = HOST_PROGRAMS.contains(&context.program)
+            && context.first_argument
+            && HOST_REGEX.is_match(value)
+            && !value.chars().all(|c| c.is_ascii_digit())
+            && (value.contains('@') || !value.contains('.'));
+        domain || host_argument
+    }
+}
+
+struct DirDetector;
+
+impl Detector for DirDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::Dir
+    }
+
+    fn detect(&self, value: &str, context: &WordContext) -> bool {
+        let expected = DIR_PROGRAMS.contains(&context.program)
+            || context
+                .option
+                .is_some_and(|option| DIR_OPTIONS.contains(&option));
+        is_path(value) && value != "/" && (value.ends_with('/') || expected)
+    }
+}
+
+struct FileDetector;
+
+impl Detector for FileDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::File
+    }
+
+    fn detect(&self, value: &str, _context: &WordContext) -> bool {
+        if !is_path(value) || value.ends_with('/') {
+            return false;
+        }
+        let name = value.rsplit('/').next().unwrap_or(value);
+        let extension = name.rsplit_once('.').is_some_and(|(_, extension)| {
+            extension.chars().all(|c| c.is_ascii_alphanumeric())
+                && extension.chars().any(|c| c.is_ascii_alphabetic())
+        });
+        let rooted = ["/", "./", "../", "~/"]
+            .iter()
+            .any(|prefix| value.starts_with(prefix));
+        extension || rooted
+    }
+}
+
+struct GitRefDetector;
+
+impl Detector for GitRefDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::GitRef
+    }
+
+    fn detect(&self, value: &str, context: &WordContext) -> bool {
+        context.program == "git"
+            && context
+                .subcommand
+                .is_some_and(|subcommand| GIT_REF_SUBCOMMANDS.contains(&subcommand))
+            && context
+                .option
+                .is_none_or(|option| GIT_REF_OPTIONS.contains(&option))
+            && GIT_REF_REGEX.is_match(value)
+            && !value.chars().all(|c| c.is_ascii_digit())
+            && !matches!(value, "origin" | "upstream")
+    }
+}
+
+struct NumberDetector;
+
+impl Detector for NumberDetector {
+    fn kind(&self) -> PlaceholderKind {
+        PlaceholderKind::Number
+    }
+
+    fn detect(&self, value: &str, _context: &WordContext) -> bool {
+        let mut parts = value.splitn(2, '.');
+        parts.all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+    }
+}
+
+/// Whether a value looks like a path rather than a pattern or expression. Sed style expressions
+/// such as `s/a/b/` and the bare `.` and `..` aren't treated as paths.
+fn is_path(value: &str) -> bool {
+    PATH_REGEX.is_match(value)
+        && !matches!(value, "." | ".." | "~")
+        && !(value.starts_with("s/") || value.starts_with("y/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replaced(command: &str) -> String {
+        apply_placeholders(command, &detect_placeholders(command))
+    }
+
+    #[test]
+    fn git_option_values_are_not_refs() {
+        let command = "git checkout feature/foo && git log -n 5 abc1234";
+        assert_eq!(
+            replaced(command),
+            "git checkout $REF && git log -n $NUM $SHA"
+        );
+        let kinds: Vec<PlaceholderKind> = detect_placeholders(command)
+            .iter()
+            .map(|placeholder| placeholder.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                PlaceholderKind::GitRef,
+                PlaceholderKind::Number,
+                PlaceholderKind::Sha
+            ]
+        );
+    }
+
+    #[test]
+    fn git_ref_options_still_take_refs() {
+        assert_eq!(replaced("git checkout -b topic"), "git checkout -b $REF");
+    }
+
+    #[test]
+    fn only_the_first_argument_of_a_host_program_is_a_host() {
+        assert_eq!(replaced("ssh host 'uptime'"), "ssh $HOST 'uptime'");
+        assert_eq!(replaced("ssh host ls"), "ssh $HOST ls");
+        assert_eq!(
+            replaced("ssh -p 2222 admin@10.0.0.5 'uptime'"),
+            "ssh -p $PORT $HOST 'uptime'"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::parse::parser::get_optional_input;
+use crate::parse::placeholders::{
+    Candidate, apply_placeholders, candidates, detect_placeholders, name_placeholders,
+};
+use dialoguer::console::Style;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Input, MultiSelect, Select};
+use docu::database::data_types::{PlaceholderData, PlaceholderType};
+
+/// Lists every value in the command that could become a placeholder, with the recognised ones
+/// already chosen, so the user can decide which are replaced. Each chosen value can then be
+/// renamed, typed, described and given a default. Returns the command with the chosen values
+/// replaced, along with its placeholders.
+pub fn review_placeholders(command: &str) -> (String, Vec<PlaceholderData>) {
+    let candidates = candidates(command);
+    let mut values: Vec<&Candidate> = Vec::new();
+    for candidate in &candidates {
+        if !values.iter().any(|value| value.value == candidate.value) {
+            values.push(candidate);
+        }
+    }
+    if values.is_empty() {
+        return (command.to_string(), Vec::new());
+    }
+    let proposed = detect_placeholders(command);
+    let items: Vec<String> = values
+        .iter()
+        .map(
+            |value| match proposed.iter().find(|p| p.value == value.value) {
+                Some(placeholder) => format!("{}  →  ${}", value.value, placeholder.name),
+                None => value.value.to_string(),
+            },
+        )
+        .collect();
+    let recognised: Vec<bool> = values.iter().map(|value| value.kind.is_some()).collect();
+    let chosen: Vec<&str> = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Choose the values to replace with placeholders (space to toggle)")
+        .items(&items)
+        .defaults(&recognised)
+        .interact()
+        .expect("Failed to read selection")
+        .into_iter()
+        .map(|i| values[i].value)
+        .collect();
+
+    let mut placeholders = name_placeholders(
+        candidates
+            .iter()
+            .filter(|candidate| chosen.contains(&candidate.value))
+            .cloned(),
+    );
+    let mut reviewed: Vec<PlaceholderData> = Vec::new();
+    for value in chosen {
+        let Some(proposed) = placeholders.iter().find(|p| p.value == value) else {
+            continue;
+        };
+        let name = prompt_placeholder_name(value, &proposed.name, &reviewed);
+        let kind = proposed.kind;
+        for placeholder in placeholders.iter_mut().filter(|p| p.value == value) {
+            placeholder.name = name.clone();
+        }
+        let mut placeholder = PlaceholderData::new(name);
+        placeholder.kind = prompt_type(&placeholder.name, kind.value_type(value));
+        if placeholder.kind == PlaceholderType::Enum {
+            placeholder.choices = prompt_choices(&placeholder.name, value);
+        }
+        let description = get_optional_input(&format!("Describe ${} (optional)", placeholder.name));
+        placeholder.description = non_empty(description);
+        placeholder.default = prompt_default(&placeholder, value);
+        reviewed.push(placeholder);
+    }
+    (apply_placeholders(command, &placeholders), reviewed)
+}
+
+/// Replaces the recognised values in the command without asking, as if every proposal was
+/// accepted in `review_placeholders`. Each placeholder's default is the value it replaced.
+pub fn accept_placeholders(command: &str) -> (String, Vec<PlaceholderData>) {
+    let placeholders = detect_placeholders(command);
+    let mut accepted: Vec<PlaceholderData> = Vec::new();
+    for placeholder in &placeholders {
+        if accepted.iter().all(|p| p.name != placeholder.name) {
+            let mut data = PlaceholderData::new(&placeholder.name);
+            data.kind = placeholder.kind.value_type(&placeholder.value);
+            data.default = Some(placeholder.value.clone());
+            accepted.push(data);
+        }
+    }
+    (apply_placeholders(command, &placeholders), accepted)
+}
+
+/// Asks for the name of the placeholder replacing `value`, which must be a valid shell variable
+/// name that no other placeholder in the command uses.
+fn prompt_placeholder_name(value: &str, proposed: &str, taken: &[PlaceholderData]) -> String {
+    Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Name for {value}"))
+        .default(proposed.to_string())
+        .validate_with(|name: &String| {
+            let red = Style::new().red();
+            let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                Err(red
+                    .apply_to("must be letters, digits and underscores")
+                    .to_string())
+            } else if taken.iter().any(|placeholder| placeholder.name == *name) {
+                Err(red.apply_to("is already used").to_string())
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()
+        .expect("Failed to read input")
+}
+
+fn prompt_type(name: &str, proposed: PlaceholderType) -> PlaceholderType {
+    let types = PlaceholderType::ALL;
+    let names: Vec<&str> = types.iter().map(PlaceholderType::as_str).collect();
+    let choice = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Type of ${name}"))
+        .items(&names)
+        .default(types.iter().position(|t| *t == proposed).unwrap_or(0))
+        .interact()
+        .expect("Failed to read selection");
+    types[choice]
+}
+
+/// Asks for the values an enum placeholder can take, starting with the value it replaces.
+fn prompt_choices(name: &str, value: &str) -> Vec<String> {
+    let choices: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Choices for ${name} (comma separated)"))
+        .with_initial_text(value)
+        .validate_with(|choices: &String| {
+            if choices.split(',').all(|choice| choice.trim().is_empty()) {
+                Err(Style::new().red().apply_to("cannot be empty").to_string())
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()
+        .expect("Failed to read input");
+    let mut unique: Vec<String> = Vec::new();
+    for choice in choices.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        if !unique.iter().any(|c| c == choice) {
+            unique.push(choice.to_string());
+        }
+    }
+    unique
+}
+
+/// Asks for the default of a placeholder, starting with the value it replaces when that fits its
+/// type.
+fn prompt_default(placeholder: &PlaceholderData, value: &str) -> Option<String> {
+    let initial = if placeholder.validate(value).is_ok() {
+        value
+    } else {
+        ""
+    };
+    let default: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Default for ${} (optional)", placeholder.name))
+        .with_initial_text(initial)
+        .allow_empty(true)
+        .validate_with(|default: &String| match default.trim() {
+            "" => Ok(()),
+            default => placeholder
+                .validate(default)
+                .map_err(|reason| Style::new().red().apply_to(reason).to_string()),
+        })
+        .interact_text()
+        .expect("Failed to read input");
+    non_empty(default)
+}
+
+fn non_empty(text: String) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
@@ -0,0 +1,66 @@
+use crate::parse::lexer::tokenize;
+use docu::database::data_types::ScriptletData;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Commands at least this similar are reported as near duplicates.
+pub const DUPLICATE_THRESHOLD: f64 = 0.8;
+
+static PLACEHOLDER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\{?[A-Za-z_][A-Za-z0-9_]*\}?").expect("Regex could not be compiled.")
+});
+
+/// Reduces a command to a canonical list of tokens, so that differences in whitespace, quoting
+/// style and placeholder names don't stop two commands from being recognised as the same.
+pub fn normalize_command(command: &str) -> Vec<String> {
+    tokenize(command)
+        .into_iter()
+        .map(|token| {
+            let token = token.text;
+            let token = match token.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+                Some(inner) => format!("\"{inner}\""),
+                None => token.to_string(),
+            };
+            PLACEHOLDER_REGEX.replace_all(&token, "$$_").into_owned()
+        })
+        .collect()
+}
+
+/// How similar two normalized commands are, from 0.0 (nothing in common) to 1.0 (identical),
+/// based on the number of tokens that have to be changed to turn one into the other.
+pub fn similarity(a: &[String], b: &[String]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(a, b) as f64 / longest as f64
+}
+
+/// Every scriptlet whose command is at least `threshold` similar to `command`, most similar first.
+pub fn find_similar<'a>(
+    command: &str,
+    scriptlets: &'a [ScriptletData],
+    threshold: f64,
+) -> Vec<(&'a ScriptletData, f64)> {
+    let normalized = normalize_command(command);
+    let mut similar: Vec<(&ScriptletData, f64)> = scriptlets
+        .iter()
+        .map(|s| (s, similarity(&normalized, &normalize_command(&s.command))))
+        .filter(|(_, score)| *score >= threshold)
+        .collect();
+    similar.sort_by(|a, b| b.1.total_cmp(&a.1));
+    similar
+}
+
+fn edit_distance(a: &[String], b: &[String]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_token) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_token) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_token != b_token);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
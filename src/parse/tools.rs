@@ -0,0 +1,170 @@
+use crate::parse::commands::{is_assignment, simple_commands};
+use docu::config::load::CONFIG;
+use std::collections::HashSet;
+
+/// A command that runs another command given in its arguments, such as `sudo` or `xargs`.
+#[derive(Clone, Copy)]
+struct Wrapper {
+    /// The options that take the next word as their value, separated by spaces, so the value
+    /// isn't mistaken for the command.
+    value_options: &'static str,
+    /// How many operands come before the command, such as the duration given to `timeout`.
+    operands: usize,
+    /// Whether the wrapper is worth listing as a tool itself, as `xargs` is and `sudo` isn't.
+    is_tool: bool,
+}
+
+const fn wrapper(value_options: &'static str, operands: usize, is_tool: bool) -> Wrapper {
+    Wrapper {
+        value_options,
+        operands,
+        is_tool,
+    }
+}
+
+const WRAPPERS: [(&str, Wrapper); 17] = [
+    (
+        "sudo",
+        wrapper(
+            "-u -g -h -p -C -D -r -t -U --user --group --host --prompt --chdir --role --type \
+             --other-user",
+            0,
+            false,
+        ),
+    ),
+    ("doas", wrapper("-u -C", 0, false)),
+    (
+        "env",
+        wrapper("-u -C -S --unset --chdir --split-string", 0, false),
+    ),
+    ("time", wrapper("-f -o --format --output", 0, false)),
+    ("nice", wrapper("-n --adjustment", 0, false)),
+    ("ionice", wrapper("-c -n -p --class --classdata", 0, false)),
+    ("nohup", wrapper("", 0, false)),
+    ("exec", wrapper("-a", 0, false)),
+    ("command", wrapper("", 0, false)),
+    ("builtin", wrapper("", 0, false)),
+    (
+        "stdbuf",
+        wrapper("-i -o -e --input --output --error", 0, false),
+    ),
+    (
+        "xargs",
+        wrapper(
+            "-a -d -E -I -L -n -P -s --arg-file --delimiter --max-args --max-procs --max-lines \
+             --max-chars",
+            0,
+            true,
+        ),
+    ),
+    ("timeout", wrapper("-s -k --signal --kill-after", 1, true)),
+    ("watch", wrapper("-n --interval", 0, true)),
+    ("strace", wrapper("-e -o -p -s -u -E -P", 0, true)),
+    (
+        "flock",
+        wrapper("-w -E --timeout --conflict-exit-code", 1, true),
+    ),
+    ("chroot", wrapper("--userspec --groups", 1, true)),
+];
+
+/// Tools that run a command given between one of these options and a `;` or `+`.
+const EXEC_OPTIONS: [(&str, &[&str]); 2] = [
+    ("find", &["-exec", "-execdir", "-ok", "-okdir"]),
+    ("fd", &["-x", "--exec", "-X", "--exec-batch"]),
+];
+
+/// The programs a command runs, in the order they first appear. Every simple command is
+/// included, wherever it is in the command, and wrappers such as `sudo`, `env` and `xargs` are
+/// looked through to the command they run, along with any in `[parser] wrappers` in the config.
+pub fn extract_tools(command: &str) -> Vec<&str> {
+    let mut tools = Vec::new();
+    for simple in simple_commands(command) {
+        if !simple.header {
+            command_tools(&simple.words, &mut tools);
+        }
+    }
+    let mut seen = HashSet::new();
+    tools.retain(|tool| seen.insert(*tool));
+    tools
+}
+
+fn command_tools<'a>(words: &[&'a str], tools: &mut Vec<&'a str>) {
+    let Some((&program, args)) = words.split_first() else {
+        return;
+    };
+    if program.starts_with(['$', '`']) || matches!(program, "[" | "[[") {
+        return;
+    }
+    let name = program_name(program);
+    if let Some(wrapper) = find_wrapper(name) {
+        if wrapper.is_tool {
+            tools.push(program);
+        }
+        command_tools(wrapped_command(wrapper, args), tools);
+        return;
+    }
+    tools.push(program);
+    if let Some((_, options)) = EXEC_OPTIONS.iter().find(|(tool, _)| *tool == name) {
+        let mut rest = args;
+        while let Some(start) = rest.iter().position(|arg| options.contains(arg)) {
+            rest = &rest[start + 1..];
+            let end = rest
+                .iter()
+                .position(|arg| matches!(*arg, "\\;" | "';'" | "\";\"" | "+"))
+                .unwrap_or(rest.len());
+            command_tools(&rest[..end], tools);
+            rest = &rest[end..];
+        }
+    }
+}
+
+/// The words of the command run by a wrapper such as `sudo`, looking through nested wrappers, or
+/// the words themselves if the command isn't wrapped.
+pub fn unwrap_command<'w, 'a>(mut words: &'w [&'a str]) -> &'w [&'a str] {
+    while let Some((program, args)) = words.split_first()
+        && let Some(wrapper) = find_wrapper(program_name(program))
+    {
+        words = wrapped_command(wrapper, args);
+    }
+    words
+}
+
+/// The name of a program without its directory, e.g. `sudo` for `/usr/bin/sudo`.
+pub fn program_name(program: &str) -> &str {
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+fn find_wrapper(name: &str) -> Option<Wrapper> {
+    WRAPPERS
+        .iter()
+        .find(|(wrapper, _)| *wrapper == name)
+        .map(|(_, wrapper)| *wrapper)
+        .or_else(|| {
+            CONFIG
+                .parser
+                .wrappers
+                .iter()
+                .flatten()
+                .any(|wrapper| wrapper == name)
+                .then_some(wrapper("", 0, false))
+        })
+}
+
+/// The words of the command a wrapper runs, after its options, any variable assignments and
+/// its operands.
+fn wrapped_command<'w, 'a>(wrapper: Wrapper, args: &'w [&'a str]) -> &'w [&'a str] {
+    let mut i = 0;
+    while let Some(&arg) = args.get(i) {
+        if arg == "--" {
+            i += 1;
+            break;
+        } else if wrapper.value_options.split(' ').any(|option| option == arg) {
+            i += 2;
+        } else if (arg.starts_with('-') && arg.len() > 1) || is_assignment(arg) {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    &args[(i + wrapper.operands).min(args.len())..]
+}
@@ -0,0 +1,121 @@
+use crate::database::data_types::ScriptletData;
+use crate::store::porter::stem;
+
+// The bm25 parameters SQLite's FTS5 uses.
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+/// An in-memory full text index over scriptlets. Matches are ranked with the bm25 formula FTS5
+/// uses, over the name, description, command and tags, with words reduced to their stems as the
+/// `porter` tokenizer does, so a Markdown library ranks search results like the SQLite one does.
+pub(crate) struct SearchIndex<'a> {
+    documents: Vec<(&'a ScriptletData, Vec<String>)>,
+    average_length: f64,
+}
+
+impl<'a> SearchIndex<'a> {
+    pub(crate) fn new(scriptlets: &'a [ScriptletData]) -> Self {
+        let documents: Vec<_> = scriptlets
+            .iter()
+            .map(|s| {
+                let text = format!(
+                    "{} {} {} {}",
+                    s.name,
+                    s.description,
+                    s.command,
+                    s.tags.join(" ")
+                );
+                (s, tokenize(&text))
+            })
+            .collect();
+        let total: usize = documents.iter().map(|(_, tokens)| tokens.len()).sum();
+        let average_length = total as f64 / documents.len().max(1) as f64;
+        Self {
+            documents,
+            average_length,
+        }
+    }
+
+    /// The scriptlets matching the stem of any word of the query as a prefix, most relevant first.
+    /// As in FTS5, `running` finds `run` and `runs`, but `runn` finds neither.
+    pub(crate) fn search(&self, query: &str) -> Vec<&'a ScriptletData> {
+        let terms = tokenize(query);
+        let mut scores = vec![0.0; self.documents.len()];
+        for term in &terms {
+            let frequencies: Vec<usize> = self
+                .documents
+                .iter()
+                .map(|(_, tokens)| tokens.iter().filter(|t| t.starts_with(term)).count())
+                .collect();
+            let hits = frequencies.iter().filter(|&&f| f > 0).count() as f64;
+            let rows = self.documents.len() as f64;
+            let idf = ((rows - hits + 0.5) / (hits + 0.5)).ln().max(1e-6);
+            for (i, &frequency) in frequencies.iter().enumerate() {
+                if frequency == 0 {
+                    continue;
+                }
+                let frequency = frequency as f64;
+                let length = self.documents[i].1.len() as f64;
+                scores[i] += idf * (frequency * (K1 + 1.0))
+                    / (frequency + K1 * (1.0 - B + B * length / self.average_length));
+            }
+        }
+        let mut matches: Vec<(&'a ScriptletData, f64)> = self
+            .documents
+            .iter()
+            .zip(scores)
+            .filter(|(_, score)| *score > 0.0)
+            .map(|((scriptlet, _), score)| (*scriptlet, score))
+            .collect();
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+        matches
+            .into_iter()
+            .map(|(scriptlet, _)| scriptlet)
+            .collect()
+    }
+}
+
+/// Splits text into the stems of its lowercase runs of letters and digits, like FTS5's `porter`
+/// tokenizer.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| stem(&token.to_lowercase()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scriptlet(id: i64, name: &str, description: &str) -> ScriptletData {
+        ScriptletData {
+            id,
+            name: name.to_string(),
+            command: "true".to_string(),
+            description: description.to_string(),
+            tags: Vec::new(),
+            created_at: "2024-01-01 00:00:00".to_string(),
+            updated_at: "2024-01-01 00:00:00".to_string(),
+            source_url: None,
+            cwd: None,
+            host: None,
+            placeholders: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn matches_other_forms_of_a_word() {
+        let scriptlets = [
+            scriptlet(1, "containers", "List the running containers"),
+            scriptlet(2, "images", "Remove dangling images"),
+        ];
+        let index = SearchIndex::new(&scriptlets);
+
+        let ids = |query| index.search(query).iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids("runs"), [1]);
+        assert_eq!(ids("container"), [1]);
+        assert_eq!(ids("image"), [2]);
+        assert!(ids("runn").is_empty());
+    }
+}
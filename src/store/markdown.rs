@@ -0,0 +1,757 @@
+use crate::database::data_types::{
+    NewScriptlet, PlaceholderData, PlaceholderType, RevisionData, ScriptletData, ScriptletFilter,
+    SortOrder, TimeRange, ToolData, TrashedScriptletData, UsageKind,
+};
+use crate::database::timestamp::{format_timestamp, parse_timestamp};
+use crate::errors::error::DocuError;
+use crate::errors::error::DocuError::{Markdown, Tool};
+use crate::store::index::SearchIndex;
+use crate::store::scriptlet_store::ScriptletStore;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const FRONT_MATTER_DELIMITER: &str = "+++";
+const TRASH_DIRECTORY: &str = ".trash";
+
+/// Keeps each scriptlet in its own Markdown file in a directory, so a library can be kept in git
+/// and reviewed like any other text. A file looks like:
+///
+/// ````markdown
+/// +++
+/// id = 3
+/// name = "ports"
+/// tools = ["ss"]
+/// tags = ["network"]
+/// created = "2025-03-01 09:30:00"
+/// updated = "2025-03-01 09:30:00"
+///
+/// [[placeholders]]
+/// name = "PORT"
+/// type = "int"
+/// description = "The port to look for"
+/// default = "8080"
+/// +++
+///
+/// Show what is listening on a port
+///
+/// ```sh
+/// ss -tlnp | grep $PORT
+/// ```
+/// ````
+///
+/// Removed scriptlets are moved into a `.trash` directory inside the library. Usage is not
+/// recorded, so frecency ordering falls back to the most recently added first, and no revisions
+/// are kept, as git keeps the history of the files.
+pub struct MarkdownStore {
+    directory: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FrontMatter {
+    id: i64,
+    name: String,
+    #[serde(default)]
+    tools: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated: Option<String>,
+    /// When the scriptlet was moved to the trash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deleted: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    placeholders: Vec<PlaceholderEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PlaceholderEntry {
+    name: String,
+    #[serde(rename = "type", default = "text_type")]
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    choices: Vec<String>,
+}
+
+fn text_type() -> String {
+    PlaceholderType::Text.as_str().to_string()
+}
+
+impl From<&PlaceholderData> for PlaceholderEntry {
+    fn from(placeholder: &PlaceholderData) -> Self {
+        Self {
+            name: placeholder.name.clone(),
+            kind: placeholder.kind.as_str().to_string(),
+            description: placeholder.description.clone(),
+            default: placeholder.default.clone(),
+            choices: placeholder.choices.clone(),
+        }
+    }
+}
+
+impl From<&PlaceholderEntry> for PlaceholderData {
+    fn from(entry: &PlaceholderEntry) -> Self {
+        Self {
+            name: entry.name.clone(),
+            kind: PlaceholderType::from_name(&entry.kind),
+            description: entry.description.clone(),
+            default: entry.default.clone(),
+            choices: entry.choices.clone(),
+        }
+    }
+}
+
+struct Entry {
+    path: PathBuf,
+    trashed: bool,
+    /// When the file was last modified, used for files written by hand without timestamps.
+    modified: String,
+    front_matter: FrontMatter,
+    description: String,
+    command: String,
+}
+
+impl Entry {
+    fn created_at(&self) -> String {
+        self.front_matter
+            .created
+            .clone()
+            .unwrap_or_else(|| self.modified.clone())
+    }
+
+    /// When the scriptlet was moved to the trash, for files trashed by hand without a timestamp
+    /// when the file was last modified.
+    fn deleted_at(&self) -> String {
+        self.front_matter
+            .deleted
+            .clone()
+            .unwrap_or_else(|| self.modified.clone())
+    }
+
+    fn created_within(&self, range: &TimeRange) -> bool {
+        *range == TimeRange::default()
+            || parse_timestamp(&self.created_at()).is_some_and(|created| range.contains(created))
+    }
+
+    fn to_scriptlet_data(&self) -> ScriptletData {
+        let created_at = self.created_at();
+        ScriptletData {
+            id: self.front_matter.id,
+            name: self.front_matter.name.clone(),
+            command: self.command.clone(),
+            description: self.description.clone(),
+            tags: self.front_matter.tags.clone(),
+            updated_at: self
+                .front_matter
+                .updated
+                .clone()
+                .unwrap_or_else(|| created_at.clone()),
+            created_at,
+            source_url: self.front_matter.source_url.clone(),
+            cwd: self.front_matter.cwd.clone(),
+            host: self.front_matter.host.clone(),
+            placeholders: self
+                .front_matter
+                .placeholders
+                .iter()
+                .map(PlaceholderData::from)
+                .collect(),
+        }
+    }
+}
+
+impl MarkdownStore {
+    pub fn open(directory: impl Into<PathBuf>) -> Result<Self, DocuError> {
+        let directory = directory.into();
+        if !directory.is_dir() {
+            return Err(Markdown(format!(
+                "{} is not a directory",
+                directory.display()
+            )));
+        }
+        Ok(Self { directory })
+    }
+
+    /// Every scriptlet in the library, including the trash.
+    fn load(&self) -> Result<Vec<Entry>, DocuError> {
+        let mut entries = load_directory(&self.directory, false)?;
+        let trash = self.directory.join(TRASH_DIRECTORY);
+        if trash.is_dir() {
+            entries.extend(load_directory(&trash, true)?);
+        }
+        Ok(entries)
+    }
+
+    /// The scriptlets outside the trash added within `created`, most recently added first.
+    fn load_scriptlets(&self, created: &TimeRange) -> Result<Vec<ScriptletData>, DocuError> {
+        let mut scriptlets: Vec<ScriptletData> = self
+            .load()?
+            .iter()
+            .filter(|entry| !entry.trashed && entry.created_within(created))
+            .map(Entry::to_scriptlet_data)
+            .collect();
+        scriptlets.sort_by_key(|s| Reverse(s.id));
+        Ok(scriptlets)
+    }
+
+    /// Where a scriptlet should be saved, named after the scriptlet unless another scriptlet or a
+    /// file that isn't a scriptlet, such as a README, already uses that file name.
+    fn path_for(&self, id: i64, name: &str, trashed: bool, entries: &[Entry]) -> PathBuf {
+        let directory = if trashed {
+            self.directory.join(TRASH_DIRECTORY)
+        } else {
+            self.directory.clone()
+        };
+        let path = directory.join(format!("{}.md", slug(name)));
+        let taken = match entries.iter().find(|entry| entry.path == path) {
+            Some(entry) => entry.front_matter.id != id,
+            None => path.exists(),
+        };
+        if taken {
+            directory.join(format!("{}-{id}.md", slug(name)))
+        } else {
+            path
+        }
+    }
+
+    /// Writes an entry to `path`, removing the file it was previously saved in if that differs.
+    fn save(&self, entry: &Entry, path: &Path) -> Result<(), DocuError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temporary = path.with_extension("md.tmp");
+        fs::write(&temporary, to_markdown(entry)?)?;
+        fs::rename(&temporary, path)?;
+        if entry.path != path && entry.path.exists() {
+            fs::remove_file(&entry.path)?;
+        }
+        Ok(())
+    }
+
+    /// Moves scriptlets between the library and its trash, returning the ids that were moved.
+    fn move_to(&self, ids: &[i64], trashed: bool) -> Result<Vec<i64>, DocuError> {
+        let mut entries = self.load()?;
+        let mut moved = Vec::new();
+        for &id in ids {
+            let Some(i) = entries
+                .iter()
+                .position(|e| e.front_matter.id == id && e.trashed != trashed)
+            else {
+                continue;
+            };
+            let path = self.path_for(id, &entries[i].front_matter.name, trashed, &entries);
+            let entry = &mut entries[i];
+            entry.front_matter.deleted = trashed.then(|| format_timestamp(SystemTime::now()));
+            self.save(entry, &path)?;
+            entry.path = path;
+            entry.trashed = trashed;
+            moved.push(id);
+        }
+        Ok(moved)
+    }
+
+    /// The scriptlet with this id outside the trash.
+    fn find(entries: &mut [Entry], id: i64) -> Result<&mut Entry, DocuError> {
+        entries
+            .iter_mut()
+            .find(|entry| entry.front_matter.id == id && !entry.trashed)
+            .ok_or_else(|| Markdown(format!("no scriptlet with id {id}")))
+    }
+
+    /// Replaces tool `from` with `to` on every scriptlet using it, including those in the trash,
+    /// returning how many scriptlets were changed.
+    fn replace_tool(
+        &self,
+        entries: &mut [Entry],
+        from: &str,
+        to: &str,
+    ) -> Result<usize, DocuError> {
+        let mut changed = 0;
+        for entry in entries
+            .iter_mut()
+            .filter(|entry| entry.front_matter.tools.iter().any(|tool| tool == from))
+        {
+            let mut tools = Vec::new();
+            for tool in &entry.front_matter.tools {
+                let tool = if tool == from { to } else { tool };
+                if !tools.iter().any(|t| t == tool) {
+                    tools.push(tool.to_string());
+                }
+            }
+            entry.front_matter.tools = tools;
+            self.save(entry, &entry.path)?;
+            changed += 1;
+        }
+        Ok(changed)
+    }
+}
+
+impl ScriptletStore for MarkdownStore {
+    fn add(&self, scriptlet: &NewScriptlet) -> Result<i64, DocuError> {
+        let entries = self.load()?;
+        let id = entries.iter().map(|e| e.front_matter.id).max().unwrap_or(0) + 1;
+        let path = self.path_for(id, &scriptlet.name, false, &entries);
+        let now = format_timestamp(scriptlet.created_at.unwrap_or_else(SystemTime::now));
+        let entry = Entry {
+            path: path.clone(),
+            trashed: false,
+            modified: now.clone(),
+            front_matter: FrontMatter {
+                id,
+                name: scriptlet.name.clone(),
+                tools: scriptlet.tools.clone(),
+                tags: sorted(scriptlet.tags.clone()),
+                created: Some(now.clone()),
+                updated: Some(now),
+                deleted: None,
+                source_url: scriptlet.source_url.clone(),
+                cwd: scriptlet.cwd.clone(),
+                host: scriptlet.host.clone(),
+                placeholders: scriptlet
+                    .placeholders
+                    .iter()
+                    .map(PlaceholderEntry::from)
+                    .collect(),
+            },
+            description: scriptlet.description.clone(),
+            command: scriptlet.command.clone(),
+        };
+        self.save(&entry, &path)?;
+        Ok(id)
+    }
+
+    fn get(&self, id: i64) -> Result<Option<ScriptletData>, DocuError> {
+        Ok(self
+            .load()?
+            .iter()
+            .find(|entry| entry.front_matter.id == id && !entry.trashed)
+            .map(Entry::to_scriptlet_data))
+    }
+
+    fn find_by_name(&self, name: &str) -> Result<Option<i64>, DocuError> {
+        Ok(self
+            .load()?
+            .iter()
+            .find(|entry| entry.front_matter.name == name)
+            .map(|entry| entry.front_matter.id))
+    }
+
+    fn update(&self, id: i64, scriptlet: &NewScriptlet) -> Result<(), DocuError> {
+        let entries = self.load()?;
+        let old = entries
+            .iter()
+            .find(|entry| entry.front_matter.id == id)
+            .ok_or_else(|| Markdown(format!("no scriptlet with id {id}")))?;
+        let tools = if old.command == scriptlet.command {
+            old.front_matter.tools.clone()
+        } else {
+            scriptlet.tools.clone()
+        };
+        let mut tags = old.front_matter.tags.clone();
+        tags.extend(scriptlet.tags.iter().cloned());
+        let placeholders = if scriptlet.placeholders.is_empty() {
+            old.to_scriptlet_data()
+                .placeholders
+                .into_iter()
+                .filter(|placeholder| placeholder.used_in(&scriptlet.command))
+                .collect()
+        } else {
+            scriptlet.placeholders.clone()
+        };
+        let now = format_timestamp(SystemTime::now());
+        let entry = Entry {
+            path: old.path.clone(),
+            trashed: false,
+            modified: now.clone(),
+            front_matter: FrontMatter {
+                id,
+                name: scriptlet.name.clone(),
+                tools,
+                tags: sorted(tags),
+                created: Some(old.created_at()),
+                updated: Some(now),
+                deleted: None,
+                source_url: scriptlet
+                    .source_url
+                    .clone()
+                    .or_else(|| old.front_matter.source_url.clone()),
+                cwd: scriptlet
+                    .cwd
+                    .clone()
+                    .or_else(|| old.front_matter.cwd.clone()),
+                host: scriptlet
+                    .host
+                    .clone()
+                    .or_else(|| old.front_matter.host.clone()),
+                placeholders: placeholders.iter().map(PlaceholderEntry::from).collect(),
+            },
+            description: scriptlet.description.clone(),
+            command: scriptlet.command.clone(),
+        };
+        let path = self.path_for(id, &scriptlet.name, false, &entries);
+        self.save(&entry, &path)
+    }
+
+    fn list(&self, filter: &ScriptletFilter) -> Result<Vec<ScriptletData>, DocuError> {
+        let mut entries: Vec<Entry> = self
+            .load()?
+            .into_iter()
+            .filter(|entry| !entry.trashed && entry.created_within(&filter.created))
+            .filter(|entry| {
+                filter
+                    .tool
+                    .as_ref()
+                    .is_none_or(|tool| entry.front_matter.tools.contains(tool))
+            })
+            .filter(|entry| {
+                filter
+                    .tag
+                    .as_ref()
+                    .is_none_or(|tag| entry.front_matter.tags.contains(tag))
+            })
+            .collect();
+        entries.sort_by_key(|entry| Reverse(entry.front_matter.id));
+        Ok(entries.iter().map(Entry::to_scriptlet_data).collect())
+    }
+
+    fn search(
+        &self,
+        query: &str,
+        sort: Option<SortOrder>,
+        created: &TimeRange,
+    ) -> Result<Vec<ScriptletData>, DocuError> {
+        let scriptlets = self.load_scriptlets(created)?;
+        let mut matches: Vec<ScriptletData> = SearchIndex::new(&scriptlets)
+            .search(query)
+            .into_iter()
+            .cloned()
+            .collect();
+        if let Some(SortOrder::Recent) = sort {
+            matches.sort_by_key(|s| Reverse(s.id));
+        }
+        Ok(matches)
+    }
+
+    fn remove(&self, ids: &[i64]) -> Result<Vec<i64>, DocuError> {
+        self.move_to(ids, true)
+    }
+
+    fn restore(&self, ids: &[i64]) -> Result<Vec<i64>, DocuError> {
+        self.move_to(ids, false)
+    }
+
+    fn trashed(&self) -> Result<Vec<TrashedScriptletData>, DocuError> {
+        let mut trashed: Vec<TrashedScriptletData> = self
+            .load()?
+            .iter()
+            .filter(|entry| entry.trashed)
+            .map(|entry| TrashedScriptletData {
+                scriptlet: entry.to_scriptlet_data(),
+                deleted_at: entry.deleted_at(),
+            })
+            .collect();
+        trashed.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(trashed)
+    }
+
+    fn empty_trash(&self, older_than: Duration) -> Result<usize, DocuError> {
+        // Nothing can have been removed before the earliest time the clock can represent.
+        let Some(cutoff) = SystemTime::now().checked_sub(older_than) else {
+            return Ok(0);
+        };
+        let mut deleted = 0;
+        for entry in self.load()?.iter().filter(|entry| entry.trashed) {
+            if parse_timestamp(&entry.deleted_at()).is_some_and(|time| time <= cutoff) {
+                fs::remove_file(&entry.path)?;
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    fn tag(&self, id: i64, tags: &[String]) -> Result<(), DocuError> {
+        let mut entries = self.load()?;
+        let entry = Self::find(&mut entries, id)?;
+        let mut all = entry.front_matter.tags.clone();
+        all.extend(tags.iter().cloned());
+        entry.front_matter.tags = sorted(all);
+        self.save(entry, &entry.path)
+    }
+
+    fn untag(&self, id: i64, tags: &[String]) -> Result<(), DocuError> {
+        let mut entries = self.load()?;
+        let entry = Self::find(&mut entries, id)?;
+        entry.front_matter.tags.retain(|tag| !tags.contains(tag));
+        self.save(entry, &entry.path)
+    }
+
+    fn revisions(&self, _id: i64) -> Result<Vec<RevisionData>, DocuError> {
+        Ok(Vec::new())
+    }
+
+    fn record_usage(&self, _id: i64, _kind: UsageKind) -> Result<(), DocuError> {
+        Ok(())
+    }
+
+    fn tools(&self) -> Result<Vec<ToolData>, DocuError> {
+        let mut counts: BTreeMap<String, i64> = BTreeMap::new();
+        for entry in self.load()?.iter().filter(|entry| !entry.trashed) {
+            let mut tools = entry.front_matter.tools.clone();
+            tools.sort();
+            tools.dedup();
+            for tool in tools {
+                *counts.entry(tool).or_default() += 1;
+            }
+        }
+        let mut tools: Vec<ToolData> = counts
+            .into_iter()
+            .map(|(name, scriptlet_count)| ToolData {
+                name,
+                scriptlet_count,
+                last_used: None,
+            })
+            .collect();
+        tools.sort_by_key(|tool| Reverse(tool.scriptlet_count));
+        Ok(tools)
+    }
+
+    /// Tools are only listed on the scriptlets using them, so there are never any to prune.
+    fn prune_tools(&self) -> Result<(usize, Vec<String>), DocuError> {
+        Ok((0, Vec::new()))
+    }
+
+    fn merge_tools(&self, from: &str, into: &str) -> Result<usize, DocuError> {
+        if from == into {
+            return Err(Tool(format!("cannot merge '{from}' into itself")));
+        }
+        let moved = self.replace_tool(&mut self.load()?, from, into)?;
+        if moved == 0 {
+            return Err(Tool(format!("no tool named '{from}'")));
+        }
+        Ok(moved)
+    }
+
+    fn rename_tool(&self, from: &str, to: &str) -> Result<(), DocuError> {
+        let mut entries = self.load()?;
+        let uses = |name: &str| {
+            entries
+                .iter()
+                .any(|entry| entry.front_matter.tools.iter().any(|tool| tool == name))
+        };
+        if !uses(from) {
+            return Err(Tool(format!("no tool named '{from}'")));
+        }
+        if uses(to) {
+            return Err(Tool(format!(
+                "a tool named '{to}' already exists, use `docu tools merge {from} {to}` instead"
+            )));
+        }
+        self.replace_tool(&mut entries, from, to)?;
+        Ok(())
+    }
+}
+
+/// Loads the scriptlets in a directory. Markdown files without front matter, such as a README
+/// describing the library, aren't scriptlets and are left alone.
+fn load_directory(directory: &Path, trashed: bool) -> Result<Vec<Entry>, DocuError> {
+    let mut entries = Vec::new();
+    for dir_entry in fs::read_dir(directory)? {
+        let path = dir_entry?.path();
+        if path.extension().is_some_and(|ext| ext == "md") && path.is_file() {
+            let text = fs::read_to_string(&path)?;
+            if !text.starts_with(FRONT_MATTER_DELIMITER) {
+                continue;
+            }
+            let modified = format_timestamp(fs::metadata(&path)?.modified()?);
+            entries.push(from_markdown(path, trashed, modified, &text)?);
+        }
+    }
+    Ok(entries)
+}
+
+fn from_markdown(
+    path: PathBuf,
+    trashed: bool,
+    modified: String,
+    text: &str,
+) -> Result<Entry, DocuError> {
+    let invalid = |reason: String| Markdown(format!("{}: {reason}", path.display()));
+    let text = text.replace("\r\n", "\n");
+    let (front_matter, body) = text
+        .strip_prefix(&format!("{FRONT_MATTER_DELIMITER}\n"))
+        .and_then(|rest| rest.split_once(&format!("\n{FRONT_MATTER_DELIMITER}\n")))
+        .ok_or_else(|| invalid(format!("missing {FRONT_MATTER_DELIMITER} front matter")))?;
+    let front_matter: FrontMatter =
+        toml::from_str(front_matter).map_err(|e| invalid(e.to_string()))?;
+    let (description, command) = split_command(body)
+        .ok_or_else(|| invalid("missing a fenced code block with the command".to_string()))?;
+    Ok(Entry {
+        path,
+        trashed,
+        modified,
+        front_matter,
+        description,
+        command,
+    })
+}
+
+/// Splits the body of a file into the description and the command. The command is the last
+/// fenced code block marked `sh`, which is how docu writes it after the description, or the last
+/// fenced code block in a hand-written file. Text on either side of it, including any other code
+/// blocks, makes up the description.
+fn split_command(body: &str) -> Option<(String, String)> {
+    let lines: Vec<&str> = body.lines().collect();
+    let blocks = code_blocks(&lines);
+    let &(start, end) = blocks
+        .iter()
+        .rev()
+        .find(|&&(start, _)| lines[start].trim_start().trim_start_matches('`').trim() == "sh")
+        .or_else(|| blocks.last())?;
+    let command = lines[start + 1..end].join("\n");
+    let description = [lines[..start].join("\n"), lines[end + 1..].join("\n")]
+        .iter()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Some((description, command))
+}
+
+/// The lines opening and closing each fenced code block. A block left open has no command.
+fn code_blocks(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = lines[start..]
+        .iter()
+        .position(|line| line.trim_start().starts_with("```"))
+    {
+        let open = start + offset;
+        let fence = lines[open]
+            .trim_start()
+            .chars()
+            .take_while(|&c| c == '`')
+            .count();
+        let Some(length) = lines[open + 1..].iter().position(|line| {
+            let line = line.trim();
+            line.len() >= fence && line.chars().all(|c| c == '`')
+        }) else {
+            break;
+        };
+        let close = open + 1 + length;
+        blocks.push((open, close));
+        start = close + 1;
+    }
+    blocks
+}
+
+fn to_markdown(entry: &Entry) -> Result<String, DocuError> {
+    let front_matter = toml::to_string(&entry.front_matter).map_err(|e| Markdown(e.to_string()))?;
+    // The fence must be longer than any run of backticks in the command.
+    let longest_run = entry
+        .command
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let mut markdown =
+        format!("{FRONT_MATTER_DELIMITER}\n{front_matter}{FRONT_MATTER_DELIMITER}\n\n");
+    if !entry.description.is_empty() {
+        markdown.push_str(&format!("{}\n\n", entry.description));
+    }
+    markdown.push_str(&format!("{fence}sh\n{}\n{fence}\n", entry.command));
+    Ok(markdown)
+}
+
+/// A file name for the scriptlet, e.g. `Docker Prune!` becomes `docker-prune`.
+fn slug(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "scriptlet".to_string()
+    } else {
+        slug
+    }
+}
+
+fn sorted(mut tags: Vec<String>) -> Vec<String> {
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty directory for a test's library, removed when the test starts so reruns start
+    /// clean.
+    fn library(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("docu-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn files_without_front_matter_are_not_scriptlets() {
+        let directory = library("readme");
+        let readme = "# Team scriptlets\n\nAdd yours with `docu add`.\n";
+        fs::write(directory.join("README.md"), readme).unwrap();
+        fs::write(directory.join("notes.md"), "Some notes\n").unwrap();
+        let store = MarkdownStore::open(&directory).unwrap();
+
+        let id = store
+            .add(&NewScriptlet::new("notes", "ss -tlnp").description("Show listening ports"))
+            .unwrap();
+
+        let scriptlets = store.list(&ScriptletFilter::default()).unwrap();
+        assert_eq!(scriptlets.len(), 1);
+        assert_eq!(scriptlets[0].id, id);
+        assert_eq!(scriptlets[0].command, "ss -tlnp");
+        assert_eq!(
+            fs::read_to_string(directory.join("README.md")).unwrap(),
+            readme
+        );
+        assert_eq!(
+            fs::read_to_string(directory.join("notes.md")).unwrap(),
+            "Some notes\n"
+        );
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn emptying_the_trash_of_scriptlets_older_than_the_clock_deletes_nothing() {
+        let directory = library("trash");
+        let store = MarkdownStore::open(&directory).unwrap();
+        let id = store
+            .add(&NewScriptlet::new("ports", "ss -tlnp").description("Show listening ports"))
+            .unwrap();
+        store.remove(&[id]).unwrap();
+
+        let forever = Duration::from_secs(u64::MAX);
+        assert_eq!(store.empty_trash(forever).unwrap(), 0);
+        assert_eq!(store.trashed().unwrap().len(), 1);
+        assert_eq!(store.empty_trash(Duration::ZERO).unwrap(), 1);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
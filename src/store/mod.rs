@@ -0,0 +1,5 @@
+mod index;
+pub mod markdown;
+mod porter;
+pub mod scriptlet_store;
+pub mod sqlite;
@@ -0,0 +1,278 @@
+// A port of the Porter stemmer in SQLite's FTS5 `porter` tokenizer, so a Markdown library
+// matches the same words as the database's full text index does. The rules, their order and the
+// conditions on the measure of the stem follow `fts5PorterCb` in SQLite's `fts5_tokenize.c`.
+
+/// Tokens outside these lengths, in bytes, are left as they are, as FTS5 does.
+const MIN_LENGTH: usize = 3;
+const MAX_LENGTH: usize = 64;
+
+/// A suffix, what it is replaced with, and the condition the rest of the word must meet.
+type Rule = (&'static str, &'static str, fn(&[u8]) -> bool);
+
+const STEP_2: [Rule; 21] = [
+    ("ational", "ate", m_gt0),
+    ("tional", "tion", m_gt0),
+    ("enci", "ence", m_gt0),
+    ("anci", "ance", m_gt0),
+    ("izer", "ize", m_gt0),
+    ("logi", "log", m_gt0),
+    ("bli", "ble", m_gt0),
+    ("alli", "al", m_gt0),
+    ("entli", "ent", m_gt0),
+    ("eli", "e", m_gt0),
+    ("ousli", "ous", m_gt0),
+    ("ization", "ize", m_gt0),
+    ("ation", "ate", m_gt0),
+    ("ator", "ate", m_gt0),
+    ("alism", "al", m_gt0),
+    ("iveness", "ive", m_gt0),
+    ("fulness", "ful", m_gt0),
+    ("ousness", "ous", m_gt0),
+    ("aliti", "al", m_gt0),
+    ("iviti", "ive", m_gt0),
+    ("biliti", "ble", m_gt0),
+];
+
+const STEP_3: [Rule; 7] = [
+    ("ical", "ic", m_gt0),
+    ("ness", "", m_gt0),
+    ("icate", "ic", m_gt0),
+    ("iciti", "ic", m_gt0),
+    ("ful", "", m_gt0),
+    ("ative", "", m_gt0),
+    ("alize", "al", m_gt0),
+];
+
+const STEP_4: [Rule; 19] = [
+    ("al", "", m_gt1),
+    ("ance", "", m_gt1),
+    ("ence", "", m_gt1),
+    ("er", "", m_gt1),
+    ("ic", "", m_gt1),
+    ("able", "", m_gt1),
+    ("ible", "", m_gt1),
+    ("ant", "", m_gt1),
+    ("ement", "", m_gt1),
+    ("ment", "", m_gt1),
+    ("ent", "", m_gt1),
+    ("ion", "", m_gt1_and_s_or_t),
+    ("ou", "", m_gt1),
+    ("ism", "", m_gt1),
+    ("ate", "", m_gt1),
+    ("iti", "", m_gt1),
+    ("ous", "", m_gt1),
+    ("ive", "", m_gt1),
+    ("ize", "", m_gt1),
+];
+
+/// Reduces a lowercase token to its stem, e.g. `running` and `runs` both become `run`. Tokens
+/// that aren't ASCII are left as they are.
+pub(crate) fn stem(token: &str) -> String {
+    if !(MIN_LENGTH..=MAX_LENGTH).contains(&token.len()) || !token.is_ascii() {
+        return token.to_string();
+    }
+    let mut word = token.as_bytes().to_vec();
+
+    step_1a(&mut word);
+    if step_1b(&mut word) && !step_1b2(&mut word) {
+        let n = word.len();
+        let last = word[n - 1];
+        if n > 1 && !is_vowel(last, false) && !b"lsz".contains(&last) && last == word[n - 2] {
+            word.pop();
+        } else if m_eq1(&word) && o_star(&word) {
+            word.push(b'e');
+        }
+    }
+
+    // Step 1c.
+    let n = word.len();
+    if word[n - 1] == b'y' && has_vowel(&word[..n - 1]) {
+        word[n - 1] = b'i';
+    }
+
+    // Within each of steps 2 to 4 only the first suffix that matches is tried, whether or not
+    // the rest of the word meets its condition.
+    apply(&mut word, &STEP_2);
+    apply(&mut word, &STEP_3);
+    apply(&mut word, &STEP_4);
+
+    // Step 5a.
+    let n = word.len();
+    if word[n - 1] == b'e' {
+        let rest = &word[..n - 1];
+        if m_gt1(rest) || (m_eq1(rest) && !o_star(rest)) {
+            word.pop();
+        }
+    }
+
+    // Step 5b.
+    let n = word.len();
+    if n > 1 && word[n - 1] == b'l' && word[n - 2] == b'l' && m_gt1(&word[..n - 1]) {
+        word.pop();
+    }
+
+    String::from_utf8(word).expect("stemming ASCII keeps it ASCII")
+}
+
+fn step_1a(word: &mut Vec<u8>) {
+    let n = word.len();
+    if word[n - 1] != b's' {
+        return;
+    }
+    if word[n - 2] == b'e' {
+        if (n > 4 && word[n - 4] == b's' && word[n - 3] == b's') || (n > 3 && word[n - 3] == b'i') {
+            word.truncate(n - 2);
+        } else {
+            word.truncate(n - 1);
+        }
+    } else if word[n - 2] != b's' {
+        word.truncate(n - 1);
+    }
+}
+
+/// Removes `ed` or `ing`, returning whether one was removed.
+fn step_1b(word: &mut Vec<u8>) -> bool {
+    if word.len() > 3 && word.ends_with(b"eed") {
+        if m_gt0(&word[..word.len() - 3]) {
+            word.pop();
+        }
+        return false;
+    }
+    for suffix in [&b"ed"[..], b"ing"] {
+        if word.len() > suffix.len() && word.ends_with(suffix) {
+            let stem = word.len() - suffix.len();
+            if has_vowel(&word[..stem]) {
+                word.truncate(stem);
+                return true;
+            }
+            return false;
+        }
+    }
+    false
+}
+
+/// Restores the `e` of `at`, `bl` and `iz`, returning whether one was restored.
+fn step_1b2(word: &mut Vec<u8>) -> bool {
+    let restores = word.len() > 2 && [&b"at"[..], b"bl", b"iz"].iter().any(|s| word.ends_with(s));
+    if restores {
+        word.push(b'e');
+    }
+    restores
+}
+
+fn apply(word: &mut Vec<u8>, rules: &[Rule]) {
+    let rule = rules
+        .iter()
+        .find(|(suffix, _, _)| word.len() > suffix.len() && word.ends_with(suffix.as_bytes()));
+    if let Some(&(suffix, replacement, condition)) = rule {
+        let stem = word.len() - suffix.len();
+        if condition(&word[..stem]) {
+            word.truncate(stem);
+            word.extend_from_slice(replacement.as_bytes());
+        }
+    }
+}
+
+fn is_vowel(c: u8, y_is_vowel: bool) -> bool {
+    matches!(c, b'a' | b'e' | b'i' | b'o' | b'u') || (y_is_vowel && c == b'y')
+}
+
+/// The length of the shortest prefix of `stem` made of consonants, then vowels, then a
+/// consonant, or 0 if there is none. `y` is a vowel after a consonant.
+fn gobble_vc(stem: &[u8], previous_consonant: bool) -> usize {
+    let mut consonant = previous_consonant;
+    let mut i = 0;
+    while i < stem.len() {
+        consonant = !is_vowel(stem[i], consonant);
+        if !consonant {
+            break;
+        }
+        i += 1;
+    }
+    i += 1;
+    while i < stem.len() {
+        consonant = !is_vowel(stem[i], consonant);
+        if consonant {
+            return i + 1;
+        }
+        i += 1;
+    }
+    0
+}
+
+/// The measure of the stem is more than 0.
+fn m_gt0(stem: &[u8]) -> bool {
+    gobble_vc(stem, false) != 0
+}
+
+/// The measure of the stem is more than 1.
+fn m_gt1(stem: &[u8]) -> bool {
+    let n = gobble_vc(stem, false);
+    n != 0 && gobble_vc(&stem[n..], true) != 0
+}
+
+/// The measure of the stem is exactly 1.
+fn m_eq1(stem: &[u8]) -> bool {
+    let n = gobble_vc(stem, false);
+    n != 0 && gobble_vc(&stem[n..], true) == 0
+}
+
+/// The stem ends consonant, vowel, consonant, where the last consonant isn't `w`, `x` or `y`.
+fn o_star(stem: &[u8]) -> bool {
+    if stem.last().is_none_or(|last| b"wxy".contains(last)) {
+        return false;
+    }
+    let mut mask = 0u32;
+    let mut consonant = false;
+    for &c in stem {
+        consonant = !is_vowel(c, consonant);
+        mask = (mask << 1) | u32::from(consonant);
+    }
+    mask & 0b111 == 0b101
+}
+
+fn m_gt1_and_s_or_t(stem: &[u8]) -> bool {
+    matches!(stem.last(), Some(b's' | b't')) && m_gt1(stem)
+}
+
+/// The stem contains a vowel, counting `y` after the first letter.
+fn has_vowel(stem: &[u8]) -> bool {
+    stem.iter().enumerate().any(|(i, &c)| is_vowel(c, i > 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stems_words_like_fts5() {
+        for (word, expected) in [
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("cats", "cat"),
+            ("agreed", "agre"),
+            ("feed", "feed"),
+            ("running", "run"),
+            ("runs", "run"),
+            ("hoping", "hope"),
+            ("filing", "file"),
+            ("happy", "happi"),
+            ("relational", "relat"),
+            ("conditional", "condit"),
+            ("sensibility", "sensibl"),
+            ("electrical", "electr"),
+            ("adjustment", "adjust"),
+            ("controlling", "control"),
+            ("rate", "rate"),
+        ] {
+            assert_eq!(stem(word), expected, "stem of {word}");
+        }
+    }
+
+    #[test]
+    fn leaves_short_long_and_non_ascii_tokens_alone() {
+        assert_eq!(stem("is"), "is");
+        assert_eq!(stem(&"s".repeat(65)), "s".repeat(65));
+        assert_eq!(stem("größes"), "größes");
+    }
+}
@@ -0,0 +1,89 @@
+use crate::database::data_types::{
+    NewScriptlet, RevisionData, ScriptletData, ScriptletFilter, SortOrder, TimeRange, ToolData,
+    TrashedScriptletData, UsageKind,
+};
+use crate::database::location::database_path;
+use crate::errors::error::DocuError;
+use crate::store::markdown::MarkdownStore;
+use crate::store::sqlite::SqliteStore;
+use std::time::Duration;
+
+/// The operations every place scriptlets can be kept in supports. Scriptlets are identified by
+/// the id the store gave them when they were added.
+pub trait ScriptletStore {
+    /// Adds a new scriptlet, returning its id.
+    fn add(&self, scriptlet: &NewScriptlet) -> Result<i64, DocuError>;
+
+    /// The scriptlet with this id, unless it doesn't exist or has been removed.
+    fn get(&self, id: i64) -> Result<Option<ScriptletData>, DocuError>;
+
+    /// The id of the scriptlet using this name, including removed scriptlets that could still be
+    /// restored.
+    fn find_by_name(&self, name: &str) -> Result<Option<i64>, DocuError>;
+
+    /// Replaces the name, command, description and tools of a scriptlet, adding any tags to the
+    /// ones it already has. A removed scriptlet is restored.
+    fn update(&self, id: i64, scriptlet: &NewScriptlet) -> Result<(), DocuError>;
+
+    /// Every scriptlet matching the filter.
+    fn list(&self, filter: &ScriptletFilter) -> Result<Vec<ScriptletData>, DocuError>;
+
+    /// Full text search over the name, description, command and tags of the scriptlets added
+    /// within `created`, best matches first unless another order is given.
+    fn search(
+        &self,
+        query: &str,
+        sort: Option<SortOrder>,
+        created: &TimeRange,
+    ) -> Result<Vec<ScriptletData>, DocuError>;
+
+    /// Removes scriptlets, returning the ids that were actually removed.
+    fn remove(&self, ids: &[i64]) -> Result<Vec<i64>, DocuError>;
+
+    /// Brings removed scriptlets back, returning the ids that were actually restored.
+    fn restore(&self, ids: &[i64]) -> Result<Vec<i64>, DocuError>;
+
+    /// The removed scriptlets, most recently removed first.
+    fn trashed(&self) -> Result<Vec<TrashedScriptletData>, DocuError>;
+
+    /// Permanently deletes the scriptlets removed at least `older_than` ago, returning how many
+    /// were deleted.
+    fn empty_trash(&self, older_than: Duration) -> Result<usize, DocuError>;
+
+    /// Adds tags to a scriptlet, keeping the ones it already has.
+    fn tag(&self, id: i64, tags: &[String]) -> Result<(), DocuError>;
+
+    /// Removes tags from a scriptlet. Tags it doesn't have are ignored.
+    fn untag(&self, id: i64, tags: &[String]) -> Result<(), DocuError>;
+
+    /// The earlier versions of a scriptlet, oldest first.
+    fn revisions(&self, id: i64) -> Result<Vec<RevisionData>, DocuError>;
+
+    /// Notes that a scriptlet was used, for frecency ordering.
+    fn record_usage(&self, id: i64, kind: UsageKind) -> Result<(), DocuError>;
+
+    /// Every tool used by the scriptlets, with how often it is used.
+    fn tools(&self) -> Result<Vec<ToolData>, DocuError>;
+
+    /// Removes the tools no scriptlet uses any more. Returns the number of links to scriptlets
+    /// that no longer exist that were removed, and the names of the tools removed.
+    fn prune_tools(&self) -> Result<(usize, Vec<String>), DocuError>;
+
+    /// Moves every scriptlet of tool `from` onto tool `into`, so `from` is no longer used.
+    /// Returns the number of scriptlets moved.
+    fn merge_tools(&self, from: &str, into: &str) -> Result<usize, DocuError>;
+
+    /// Renames a tool, failing if a tool with the new name already exists.
+    fn rename_tool(&self, from: &str, to: &str) -> Result<(), DocuError>;
+}
+
+/// Opens the store for the library chosen with `select_database`. A directory is opened as a
+/// Markdown library, anything else as a SQLite database.
+pub fn open_store() -> Result<Box<dyn ScriptletStore>, DocuError> {
+    let path = database_path();
+    if path.is_dir() {
+        Ok(Box::new(MarkdownStore::open(path)?))
+    } else {
+        Ok(Box::new(SqliteStore))
+    }
+}
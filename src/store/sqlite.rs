@@ -0,0 +1,133 @@
+use crate::database::connect::{
+    add_scriptlet, backup_database, check_database, empty_trash, find_scriptlet_by_name,
+    get_scriptlet_by_id, get_scriptlet_revisions, get_scriptlets, get_tools,
+    get_trashed_scriptlets, merge_tools, migration_status, prune_tools, record_usage, rename_tool,
+    repair_database, restore_database, restore_scriptlets, run_migrations, search_scriptlets,
+    tag_scriptlet, trash_scriptlets, untag_scriptlet, update_scriptlet,
+};
+use crate::database::data_types::{
+    DoctorReport, NewScriptlet, RevisionData, ScriptletData, ScriptletFilter, SortOrder, TimeRange,
+    ToolData, TrashedScriptletData, UsageKind,
+};
+use crate::database::migrate::{Migration, MigrationStatus};
+use crate::errors::error::DocuError;
+use crate::store::scriptlet_store::ScriptletStore;
+use std::path::Path;
+use std::time::Duration;
+
+/// Keeps scriptlets in the SQLite database chosen with `select_database`, or the default
+/// database if none was chosen. Removed scriptlets are moved to its trash.
+pub struct SqliteStore;
+
+/// Maintenance of the database file itself, which only SQLite libraries have.
+impl SqliteStore {
+    /// Checks the database file, the full text index and the tool links without changing
+    /// anything.
+    pub fn check(&self) -> Result<DoctorReport, DocuError> {
+        check_database()
+    }
+
+    /// Rebuilds the full text index and removes dangling tool links and unused tools.
+    pub fn repair(&self) -> Result<(), DocuError> {
+        repair_database()
+    }
+
+    /// Copies the database to `path`, which is safe while other docu processes are using it.
+    pub fn backup(&self, path: &Path) -> Result<(), DocuError> {
+        backup_database(path)
+    }
+
+    /// Replaces the database with the backup at `path`, returning the schema version of the
+    /// backup, which is then migrated to the latest version.
+    pub fn restore_backup(&self, path: &Path) -> Result<i64, DocuError> {
+        restore_database(path)
+    }
+
+    pub fn migration_status(&self) -> Result<MigrationStatus, DocuError> {
+        migration_status()
+    }
+
+    /// Applies the pending migrations, returning the ones applied.
+    pub fn migrate(&self) -> Result<Vec<&'static Migration>, DocuError> {
+        run_migrations()
+    }
+}
+
+impl ScriptletStore for SqliteStore {
+    fn add(&self, scriptlet: &NewScriptlet) -> Result<i64, DocuError> {
+        add_scriptlet(scriptlet)
+    }
+
+    fn get(&self, id: i64) -> Result<Option<ScriptletData>, DocuError> {
+        get_scriptlet_by_id(id)
+    }
+
+    fn find_by_name(&self, name: &str) -> Result<Option<i64>, DocuError> {
+        find_scriptlet_by_name(name)
+    }
+
+    fn update(&self, id: i64, scriptlet: &NewScriptlet) -> Result<(), DocuError> {
+        update_scriptlet(id, scriptlet)
+    }
+
+    fn list(&self, filter: &ScriptletFilter) -> Result<Vec<ScriptletData>, DocuError> {
+        get_scriptlets(filter)
+    }
+
+    fn search(
+        &self,
+        query: &str,
+        sort: Option<SortOrder>,
+        created: &TimeRange,
+    ) -> Result<Vec<ScriptletData>, DocuError> {
+        search_scriptlets(query, sort, created)
+    }
+
+    fn remove(&self, ids: &[i64]) -> Result<Vec<i64>, DocuError> {
+        trash_scriptlets(ids)
+    }
+
+    fn restore(&self, ids: &[i64]) -> Result<Vec<i64>, DocuError> {
+        restore_scriptlets(ids)
+    }
+
+    fn trashed(&self) -> Result<Vec<TrashedScriptletData>, DocuError> {
+        get_trashed_scriptlets()
+    }
+
+    fn empty_trash(&self, older_than: Duration) -> Result<usize, DocuError> {
+        empty_trash(older_than)
+    }
+
+    fn tag(&self, id: i64, tags: &[String]) -> Result<(), DocuError> {
+        tag_scriptlet(id, tags)
+    }
+
+    fn untag(&self, id: i64, tags: &[String]) -> Result<(), DocuError> {
+        untag_scriptlet(id, tags)
+    }
+
+    fn revisions(&self, id: i64) -> Result<Vec<RevisionData>, DocuError> {
+        get_scriptlet_revisions(id)
+    }
+
+    fn record_usage(&self, id: i64, kind: UsageKind) -> Result<(), DocuError> {
+        record_usage(id, kind)
+    }
+
+    fn tools(&self) -> Result<Vec<ToolData>, DocuError> {
+        get_tools()
+    }
+
+    fn prune_tools(&self) -> Result<(usize, Vec<String>), DocuError> {
+        prune_tools()
+    }
+
+    fn merge_tools(&self, from: &str, into: &str) -> Result<usize, DocuError> {
+        merge_tools(from, into)
+    }
+
+    fn rename_tool(&self, from: &str, to: &str) -> Result<(), DocuError> {
+        rename_tool(from, to)
+    }
+}
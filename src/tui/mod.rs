@@ -1,2 +1,4 @@
+mod diff;
 pub mod print;
+pub mod select;
 mod syntax_highlight;
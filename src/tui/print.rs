@@ -1,7 +1,13 @@
-use crate::config::load::CONFIG;
-use crate::database::data_types::ScriptletData;
+use docu::config::load::CONFIG;
+use docu::database::data_types::{
+    PlaceholderData, RevisionData, ScriptletData, ToolData, TrashedScriptletData,
+};
+use docu::database::timestamp::parse_timestamp;
+use std::path::Path;
+use std::time::SystemTime;
 
-use crate::tui::syntax_highlight::highlight_code;
+use crate::tui::diff::{DiffLine, diff_lines};
+use crate::tui::syntax_highlight::{highlight_code, highlight_diff};
 use nu_ansi_term::{Color, Style};
 
 pub fn show_all_scriptlets_tui(scriptlets: Vec<ScriptletData>) {
@@ -16,28 +22,296 @@ fn convert_to_list_items(scriptlets: Vec<ScriptletData>) -> Vec<String> {
     let items: Vec<String> = scriptlets
         .into_iter()
         .flat_map(|s| {
-            vec![
-                Style::new()
-                    .fg(Color::Rgb(
-                        CONFIG.colors.scriptlet_name.unwrap_or_default().r,
-                        CONFIG.colors.scriptlet_name.unwrap_or_default().g,
-                        CONFIG.colors.scriptlet_name.unwrap_or_default().b,
-                    ))
-                    .bold()
-                    .paint(format!("{} ({})", s.name, s.id))
-                    .to_string(),
-                Style::new()
-                    .fg(Color::Rgb(
-                        CONFIG.colors.scriptlet_description.unwrap_or_default().r,
-                        CONFIG.colors.scriptlet_description.unwrap_or_default().g,
-                        CONFIG.colors.scriptlet_description.unwrap_or_default().b,
-                    ))
-                    .paint(s.description)
-                    .to_string(),
-                highlight_code(&s.command.clone()),
-                String::new(),
-            ]
+            let mut lines = vec![
+                format!(
+                    "{}{}",
+                    name_style().paint(format!("{} ({})", s.name, s.id)),
+                    paint_tags(&s.tags)
+                ),
+                paint_metadata(&s),
+                description_style().paint(&s.description).to_string(),
+                highlight_code(&s.command),
+            ];
+            lines.extend(s.placeholders.iter().map(paint_placeholder));
+            lines.push(String::new());
+            lines
         })
         .collect();
     items
 }
+
+pub fn show_trashed_scriptlets_tui(trashed: Vec<TrashedScriptletData>) {
+    println!();
+    for TrashedScriptletData {
+        scriptlet: s,
+        deleted_at,
+    } in trashed
+    {
+        println!(
+            "{}{}",
+            name_style().paint(format!("{} ({})", s.name, s.id)),
+            description_style()
+                .italic()
+                .paint(format!("  deleted {deleted_at}"))
+        );
+        println!("{}", description_style().paint(s.description));
+        println!("{}", highlight_code(&s.command));
+        println!();
+    }
+}
+
+pub fn show_tools_tui(tools: Vec<ToolData>) {
+    let width = tools
+        .iter()
+        .map(|t| t.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("TOOL".len());
+    println!();
+    println!(
+        "{}",
+        Style::new().bold().paint(format!(
+            "{:<width$}  {:>10}  LAST USED",
+            "TOOL", "SCRIPTLETS"
+        ))
+    );
+    for tool in tools {
+        println!(
+            "{}  {:>10}  {}",
+            name_style().paint(format!("{:<width$}", tool.name)),
+            tool.scriptlet_count,
+            description_style().paint(tool.last_used.unwrap_or_else(|| "never".to_string()))
+        );
+    }
+    println!();
+}
+
+/// Shows existing scriptlets that look like duplicates, with how similar each one is.
+pub fn show_similar_scriptlets_tui(similar: &[(&ScriptletData, f64)]) {
+    println!();
+    for (s, score) in similar {
+        println!(
+            "{}  {}",
+            paint_score(*score),
+            name_style().paint(format!("{} ({})", s.name, s.id))
+        );
+        println!("      {}", description_style().paint(&s.description));
+        println!("{}", indent(&highlight_code(&s.command)));
+    }
+    println!();
+}
+
+pub fn show_duplicate_pairs_tui(pairs: &[(&ScriptletData, &ScriptletData, f64)]) {
+    println!();
+    for (a, b, score) in pairs {
+        println!(
+            "{}  {} / {}",
+            paint_score(*score),
+            name_style().paint(format!("{} ({})", a.name, a.id)),
+            name_style().paint(format!("{} ({})", b.name, b.id))
+        );
+        println!("{}", indent(&highlight_code(&a.command)));
+        println!("{}", indent(&highlight_code(&b.command)));
+        println!();
+    }
+}
+
+/// Shows which of the picked history commands is being added next.
+pub fn show_import_progress_tui(position: usize, total: usize, command: &str) {
+    println!();
+    println!(
+        "{}",
+        Style::new()
+            .bold()
+            .paint(format!("Adding {position} of {total}:"))
+    );
+    println!("{}", indent(&highlight_code(command)));
+}
+
+/// Indents every line of a command, so multi-line commands line up under their scriptlet.
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("      {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn paint_score(score: f64) -> String {
+    let color = if score >= 1.0 {
+        Color::Red
+    } else {
+        Color::Yellow
+    };
+    color
+        .bold()
+        .paint(format!("{:>4.0}%", score * 100.0))
+        .to_string()
+}
+
+struct Version {
+    label: String,
+    name: String,
+    description: String,
+    command: String,
+}
+
+/// Shows every revision of a scriptlet, newest first, with the changes that were made when each
+/// revision was replaced.
+pub fn show_history_tui(current: ScriptletData, revisions: Vec<RevisionData>) {
+    println!();
+    println!(
+        "{}",
+        name_style().paint(format!("{} ({})", current.name, current.id))
+    );
+    if revisions.is_empty() {
+        println!("No previous revisions");
+        return;
+    }
+    let mut newer = Version {
+        label: "current".to_string(),
+        name: current.name,
+        description: current.description,
+        command: current.command,
+    };
+    for revision in revisions.into_iter().rev() {
+        println!();
+        println!(
+            "{}",
+            Style::new().bold().paint(format!(
+                "Revision {} -> {} (replaced {})",
+                revision.revision, newer.label, revision.time
+            ))
+        );
+        if revision.name != newer.name {
+            println!("  name: {} -> {}", revision.name, newer.name);
+        }
+        if revision.description != newer.description {
+            for line in diff_lines(&revision.description, &newer.description) {
+                println!("{}", paint_plain_diff_line(line));
+            }
+        }
+        if revision.command != newer.command {
+            for line in highlight_diff(&revision.command, &newer.command) {
+                println!("{line}");
+            }
+        }
+        newer = Version {
+            label: format!("revision {}", revision.revision),
+            name: revision.name,
+            description: revision.description,
+            command: revision.command,
+        };
+    }
+    println!();
+}
+
+fn paint_tags(tags: &[String]) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+    let tags = tags
+        .iter()
+        .map(|tag| format!("#{tag}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    description_style()
+        .italic()
+        .paint(format!("  {tags}"))
+        .to_string()
+}
+
+/// Where and when a scriptlet was saved, e.g. `added 3 days ago · in ~/src on laptop`.
+fn paint_metadata(s: &ScriptletData) -> String {
+    let mut parts = vec![format!("added {}", relative_time(&s.created_at))];
+    if s.updated_at != s.created_at {
+        parts.push(format!("updated {}", relative_time(&s.updated_at)));
+    }
+    match (&s.cwd, &s.host) {
+        (Some(cwd), Some(host)) => parts.push(format!("in {} on {host}", shorten_home(cwd))),
+        (Some(cwd), None) => parts.push(format!("in {}", shorten_home(cwd))),
+        (None, Some(host)) => parts.push(format!("on {host}")),
+        (None, None) => {}
+    }
+    if let Some(source_url) = &s.source_url {
+        parts.push(format!("from {source_url}"));
+    }
+    description_style()
+        .dimmed()
+        .italic()
+        .paint(parts.join(" · "))
+        .to_string()
+}
+
+/// A placeholder of a scriptlet, e.g. `$PORT  int, default 8080 · The port to look for`.
+fn paint_placeholder(placeholder: &PlaceholderData) -> String {
+    let mut details = vec![placeholder.kind.as_str().to_string()];
+    if !placeholder.choices.is_empty() {
+        details[0] = format!("one of {}", placeholder.choices.join(", "));
+    }
+    if let Some(default) = &placeholder.default {
+        details.push(format!("default {default}"));
+    }
+    let mut text = details.join(", ");
+    if let Some(description) = &placeholder.description {
+        text.push_str(&format!(" · {description}"));
+    }
+    format!(
+        "  {}  {}",
+        Style::new().bold().paint(format!("${}", placeholder.name)),
+        description_style().dimmed().paint(text)
+    )
+}
+
+/// Describes a stored timestamp relative to now, e.g. `2 hours ago`. Timestamps that can't be
+/// parsed are shown as they are.
+fn relative_time(timestamp: &str) -> String {
+    let Some(time) = parse_timestamp(timestamp) else {
+        return timestamp.to_string();
+    };
+    let seconds = SystemTime::now()
+        .duration_since(time)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let (amount, unit) = match seconds {
+        0..60 => return "just now".to_string(),
+        60..3_600 => (seconds / 60, "minute"),
+        3_600..86_400 => (seconds / 3_600, "hour"),
+        86_400..604_800 => (seconds / 86_400, "day"),
+        604_800..2_592_000 => (seconds / 604_800, "week"),
+        2_592_000..31_536_000 => (seconds / 2_592_000, "month"),
+        _ => (seconds / 31_536_000, "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{amount} {unit}{plural} ago")
+}
+
+fn shorten_home(path: &str) -> String {
+    match dirs::home_dir()
+        .as_deref()
+        .and_then(|home| Path::new(path).strip_prefix(home).ok())
+    {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.to_string(),
+    }
+}
+
+fn paint_plain_diff_line(line: DiffLine) -> String {
+    match line {
+        DiffLine::Same(line) => description_style().paint(format!("  {line}")).to_string(),
+        DiffLine::Removed(line) => Color::Red.paint(format!("- {line}")).to_string(),
+        DiffLine::Added(line) => Color::Green.paint(format!("+ {line}")).to_string(),
+    }
+}
+
+fn name_style() -> Style {
+    let color = CONFIG.colors.scriptlet_name.unwrap_or_default();
+    Style::new()
+        .fg(Color::Rgb(color.r, color.g, color.b))
+        .bold()
+}
+
+fn description_style() -> Style {
+    let color = CONFIG.colors.scriptlet_description.unwrap_or_default();
+    Style::new().fg(Color::Rgb(color.r, color.g, color.b))
+}
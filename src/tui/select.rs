@@ -0,0 +1,43 @@
+use crate::import::history::ImportCandidate;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{MultiSelect, Select};
+use docu::database::data_types::ScriptletData;
+
+/// Lets the user pick one of the given scriptlets. Returns `None` if the picker was cancelled.
+pub fn select_scriptlet_tui(scriptlets: &[ScriptletData]) -> Option<&ScriptletData> {
+    let items: Vec<String> = scriptlets
+        .iter()
+        .map(|s| format!("{} ({}) - {}", s.name, s.id, s.description))
+        .collect();
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select a scriptlet")
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .expect("Failed to read selection")?;
+    scriptlets.get(selection)
+}
+
+/// Lets the user tick the history commands to import. Multi-line commands are shown by their
+/// first line.
+pub fn select_commands_tui(candidates: &[ImportCandidate]) -> Vec<&ImportCandidate> {
+    let items: Vec<String> = candidates
+        .iter()
+        .map(|candidate| {
+            let mut lines = candidate.entry.command.lines();
+            let first = lines.next().unwrap_or_default();
+            let more = if lines.next().is_some() { " …" } else { "" };
+            format!("{:>4}×  {first}{more}", candidate.count)
+        })
+        .collect();
+    MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select the commands to add (space to toggle)")
+        .items(&items)
+        .max_length(20)
+        .interact_opt()
+        .expect("Failed to read selection")
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|i| candidates.get(i))
+        .collect()
+}
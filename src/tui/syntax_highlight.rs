@@ -1,18 +1,38 @@
+use crate::tui::diff::{DiffLine, diff_lines};
 use nu_ansi_term::{Color, Style};
 use std::sync::LazyLock;
 use syntect::easy::HighlightLines;
 use syntect::highlighting::{Theme, ThemeSet};
 use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
 
 static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
 static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
 
+/// Highlights a command line by line. The highlighter keeps its state between lines, so heredocs,
+/// strings and loops spanning several lines are highlighted as a whole.
 pub fn highlight_code(text: &str) -> String {
     let syntax = SYNTAX_SET
         .find_syntax_by_token("bash")
         .expect("could not find syntax");
     let mut highlighter = HighlightLines::new(syntax, get_default_theme());
-    highlight_line(&SYNTAX_SET, &mut highlighter, text)
+    LinesWithEndings::from(text)
+        .map(|line| highlight_line(&SYNTAX_SET, &mut highlighter, line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Renders a diff between two versions of a command. Unchanged lines keep their syntax
+/// highlighting, while removed and added lines are shown in red and green.
+pub fn highlight_diff(old: &str, new: &str) -> Vec<String> {
+    diff_lines(old, new)
+        .into_iter()
+        .map(|line| match line {
+            DiffLine::Same(line) => format!("  {}", highlight_code(line)),
+            DiffLine::Removed(line) => Color::Red.paint(format!("- {line}")).to_string(),
+            DiffLine::Added(line) => Color::Green.paint(format!("+ {line}")).to_string(),
+        })
+        .collect()
 }
 
 fn highlight_line(syntax_set: &SyntaxSet, highlighter: &mut HighlightLines, line: &str) -> String {
@@ -21,7 +41,11 @@ fn highlight_line(syntax_set: &SyntaxSet, highlighter: &mut HighlightLines, line
         .expect("Line could not be highlighted.");
     let styled_spans = highlighted_string
         .into_iter()
-        .map(|(style, content)| convert_syntect_style(&style).paint(content).to_string())
+        .map(|(style, content)| {
+            convert_syntect_style(&style)
+                .paint(content.trim_end_matches(['\r', '\n']))
+                .to_string()
+        })
         .collect::<Vec<String>>();
     styled_spans.join("")
 }